@@ -2,7 +2,7 @@ use smart_read::*;
 
 
 
-// take in a password input
+// take in a password input (this is just an example of a custom input, `SecretInput` is better for real passwords since it doesn't show what's typed)
 fn main() {
 	let input = read!(PasswordInput {min_len: 10, min_digits: 1});
 	println!("You entered: \"{input}\"");
@@ -15,30 +15,33 @@ struct PasswordInput {
 	pub min_digits: usize,
 }
 
+// `PromptHooks` lets you reuse the same prompt loop as the built-in types (retries, preset answers, etc)
+impl PromptHooks for PasswordInput {
+	type Output = String;
+	fn default_prompt(&self) -> String {
+		format!("Enter a password (must have {}+ characters and have {}+ digits): ", self.min_len, self.min_digits)
+	}
+	fn render_default(&self, default: &Self::Output) -> String {
+		"*".repeat(default.len())
+	}
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		Ok(input)
+	}
+	fn validate(&self, password: &Self::Output) -> Result<(), String> {
+		if password.len() < self.min_len {
+			return Err(format!("Invalid, password must have at least {} characters", self.min_len));
+		}
+		if password.chars().filter(|c| c.is_ascii_digit()).count() < self.min_digits {
+			return Err(format!("Invalid, password must have at least {} digits", self.min_digits));
+		}
+		Ok(())
+	}
+}
+
 impl TryRead for PasswordInput {
 	type Output = String;
 	type Default = (); // ensure no default can be given
-	fn try_read_line(self, prompt: Option<String>, _default: Option<Self::Default>) -> smart_read::BoxResult<Self::Output> {
-		let prompt = prompt.unwrap_or_else(
-			|| format!("Enter a password (must have {}+ characters and have {}+ digits): ", self.min_len, self.min_digits)
-		);
-		loop {
-			
-			// you could also do `let password = try_prompt!(prompt)?;`
-			print!("{prompt}");
-			let password = read_stdin()?;
-			
-			if password.len() < 10 {
-				println!("Invalid, password must have at least 10 characters");
-				continue;
-			}
-			if password.chars().filter(|c| c.is_digit(10)).count() < 1 {
-				println!("Invalid, password must have at least 1 digit");
-				continue;
-			}
-			
-			return Ok(password)
-			
-		}
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, _default: Option<Self::Default>) -> smart_read::ReadResult<Self::Output> {
+		run_prompt_loop(term, &self, prompt, None)
 	}
 }
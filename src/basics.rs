@@ -1,236 +1,374 @@
 use crate::*;
+use std::{net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr}, path::PathBuf, str::FromStr};
 
 
 
+impl DisplayPromptHooks for () {
+	type Output = String;
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		Ok(input)
+	}
+}
+
 impl TryRead for () {
 	type Output = String;
 	type Default = String;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
-		match (prompt, &default) {
-			(Some(prompt), Some(default)) => print!("{prompt}(default: {default}) "),
-			(None, Some(default)) => print!("(default: {default}) "),
-			(Some(prompt), None) => print!("{prompt}"),
-			(None, None) => {},
-		}
-		let output = read_stdin()?;
-		Ok(if output.is_empty() && let Some(default) = default {
-			default.to_string()
-		} else {
-			output
-		})
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		run_prompt_loop(term, &self, prompt, default)
 	}
 }
 
 
 
 /// Takes an input that isn't empty
+#[derive(Clone, Copy)]
 pub struct NonEmptyInput;
 
+impl DisplayPromptHooks for NonEmptyInput {
+	type Output = String;
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		if input.is_empty() {
+			return Err(String::from("Invalid input, must not be empty"));
+		}
+		Ok(input)
+	}
+}
+
 impl TryRead for NonEmptyInput {
 	type Output = String;
 	type Default = String;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
-		let mut prompt = prompt.unwrap_or_default();
-		if let Some(default) = default.as_ref() {
-			prompt += &format!("(default: {default}) ");
-		}
-		loop {
-			
-			print!("{prompt}");
-			let input = read_stdin()?;
-			if input.is_empty() {
-				println!();
-				println!("Invalid input, must not be empty");
-				continue;
-			}
-			return BoxResult::Ok(input);
-			
-		}
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		run_prompt_loop(term, &self, prompt, default)
 	}
 }
 
 
 
 /// Takes an input that contains non-whitespace chars
+#[derive(Clone, Copy)]
 pub struct NonWhitespaceInput;
 
+impl DisplayPromptHooks for NonWhitespaceInput {
+	type Output = String;
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		if input.trim().is_empty() {
+			return Err(String::from("Invalid input, must contain non-whitespace characters"));
+		}
+		Ok(input)
+	}
+}
+
 impl TryRead for NonWhitespaceInput {
 	type Output = String;
 	type Default = String;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
-		let mut prompt = prompt.unwrap_or_default();
-		if let Some(default) = default.as_ref() {
-			prompt += &format!("(default: {default}) ");
-		}
-		loop {
-			
-			print!("{prompt}");
-			let input = read_stdin()?;
-			if input.trim().is_empty() {
-				println!();
-				println!("Invalid input, must contain non-whitespace characters");
-				continue;
-			}
-			return BoxResult::Ok(input);
-			
-		}
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		run_prompt_loop(term, &self, prompt, default)
 	}
 }
 
 
 
 /// Allows you to take a bool input
+#[derive(Clone, Copy)]
 pub struct BoolInput;
 
+impl DisplayPromptHooks for BoolInput {
+	type Output = bool;
+	fn default_prompt(&self) -> String {
+		String::from("Enter a bool: ")
+	}
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		match &*input.to_lowercase() {
+			"true" | "t" => Ok(true),
+			"false" | "f" => Ok(false),
+			_ => Err(String::from("Invalid input, please enter \"true\" or \"false\"")),
+		}
+	}
+}
+
 impl TryRead for BoolInput {
 	type Output = bool;
 	type Default = bool;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
-		let mut prompt = prompt.unwrap_or(String::from("Enter a bool: "));
-		if let Some(default) = default.as_ref() {
-			prompt += &format!("(default: {default}) ");
-		}
-		loop {
-			
-			print!("{prompt}");
-			let input = read_stdin()?.to_lowercase();
-			match (&*input, default) {
-				("", Some(default)) => return Ok(default),
-				("true", _) | ("t", _) => return Ok(true),
-				("false", _) | ("f", _) => return Ok(false),
-				(_, _) => {
-					println!();
-					println!("Invalid input, please enter \"true\" or \"false\"");
-				}
-			}
-			
-		}
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		run_prompt_loop(term, &self, prompt, default)
 	}
 }
 
 
 
 /// Allows you to take a bool input
+#[derive(Clone, Copy)]
 pub struct YesNoInput;
 
+impl PromptHooks for YesNoInput {
+	type Output = bool;
+	fn default_prompt(&self) -> String {
+		String::from("Enter 'Yes' or 'No': ")
+	}
+	fn render_default(&self, default: &Self::Output) -> String {
+		String::from(if *default {"Yes"} else {"No"})
+	}
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		match &*input.to_lowercase() {
+			"yes" | "y" => Ok(true),
+			"no" | "n" => Ok(false),
+			_ => Err(String::from("Invalid input, please enter \"yes\" or \"no\"")),
+		}
+	}
+}
+
 impl TryRead for YesNoInput {
 	type Output = bool;
 	type Default = bool;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
-		let mut prompt = prompt.unwrap_or(String::from("Enter 'Yes' or 'No': "));
-		if let Some(default) = default.as_ref() {
-			prompt += &format!("(default: {}) ", if *default {"Yes"} else {"No"});
-		}
-		loop {
-			
-			print!("{prompt}");
-			let input = read_stdin()?.to_lowercase();
-			match (&*input, default) {
-				("", Some(default)) => return Ok(default),
-				("yes", _) | ("y", _) => return Ok(true),
-				("no", _) | ("n", _) => return Ok(false),
-				(_, _) => {
-					println!();
-					println!("Invalid input, please enter \"yes\" or \"no\"");
-				}
-			}
-			
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		run_prompt_loop(term, &self, prompt, default)
+	}
+}
+
+
+
+/// Allows you to take any input that implements `FromStr`, such as numbers, `IpAddr`, `SocketAddr`, `PathBuf`, or your own types
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// # use smart_read::prelude::*;
+/// use std::net::{IpAddr, SocketAddr};
+/// 
+/// let ip = read!(Parse::<IpAddr>::new());  // uses the default prompt for `IpAddr` (see `DefaultPrompt`)
+/// let address = prompt!("Server address: "; Parse::<SocketAddr>::new());
+/// let path = read!(PathInput);  // same as `Parse::<PathBuf>::with_renderer(...)`, since `PathBuf` doesn't implement `Display`
+/// ```
+/// 
+/// For types that don't implement `DefaultPrompt` (which can't be implemented for types from other crates), use `Parse::with_prompt()`:
+/// 
+/// ```ignore
+/// let id = read!(Parse::<Uuid>::with_prompt("Enter a UUID: "));
+/// ```
+pub struct Parse<T> {
+	default_prompt: &'static str,
+	render_default: fn(&T) -> String,
+}
+
+impl<T> Parse<T> {
+	/// Initializer that uses the type's default prompt
+	pub const fn new() -> Self where T: DefaultPrompt + Display {
+		Self::with_prompt(T::DEFAULT_PROMPT)
+	}
+	/// Initializer that uses the given prompt when none is given to the read
+	pub const fn with_prompt(default_prompt: &'static str) -> Self where T: Display {
+		Self::with_renderer(default_prompt, render_with_display::<T>)
+	}
+	/// Initializer for types that don't implement `Display`, the given function is used to show default values
+	pub const fn with_renderer(default_prompt: &'static str, render_default: fn(&T) -> String) -> Self {
+		Self {
+			default_prompt,
+			render_default,
 		}
 	}
 }
 
+fn render_with_display<T: Display>(value: &T) -> String {
+	value.to_string()
+}
+
+impl<T: DefaultPrompt + Display> Default for Parse<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> Clone for Parse<T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
 
+impl<T> Copy for Parse<T> {}
 
-macro_rules! implement_number_input {
-	($type_name:tt, $type_base:ty, $default_prompt:expr) => {
-		impl TryRead for $type_name {
-			type Output = $type_base;
-			type Default = $type_base;
-			fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
-				let mut prompt = prompt.unwrap_or(String::from($default_prompt));
-				if let Some(default) = default.as_ref() {
-					prompt += &format!("(default: {default}) ");
-				}
-				loop {
-					
-					print!("{prompt}");
-					let input_string = read_stdin()?;
-					if input_string.is_empty() && let Some(default) = default {
-						return Ok(default);
-					}
-					
-					let input = match input_string.parse::<$type_base>() {
-						Ok(v) => v,
-						Err(err) => {
-							println!();
-							println!("Could not parse input (error: {err})");
-							continue;
-						}
-					};
-					return Ok(input);
-					
-				}
+impl<T> PromptHooks for Parse<T>
+where
+	T: FromStr,
+	<T as FromStr>::Err: Display,
+{
+	type Output = T;
+	fn default_prompt(&self) -> String {
+		String::from(self.default_prompt)
+	}
+	fn render_default(&self, default: &Self::Output) -> String {
+		(self.render_default)(default)
+	}
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		input.parse::<T>().map_err(|err| format!("Could not parse input (error: {err})"))
+	}
+}
+
+impl<T> TryRead for Parse<T>
+where
+	T: FromStr,
+	<T as FromStr>::Err: Display,
+{
+	type Output = T;
+	type Default = T;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		run_prompt_loop(term, &self, prompt, default)
+	}
+}
+
+
+
+/// Gives the prompt that `Parse::new()` uses for a type
+/// 
+/// This can be implemented for your own types:
+/// 
+/// ```
+/// # use smart_read::prelude::*;
+/// # use std::{fmt, str::FromStr};
+/// struct Color(u8, u8, u8);
+/// # impl FromStr for Color {type Err = String; fn from_str(_: &str) -> Result<Self, String> {Ok(Color(0, 0, 0))}}
+/// # impl fmt::Display for Color {fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)}}
+/// 
+/// impl DefaultPrompt for Color {
+/// 	const DEFAULT_PROMPT: &'static str = "Enter a color (like #ff8000): ";
+/// }
+/// 
+/// let color_input = Parse::<Color>::new();
+/// ```
+pub trait DefaultPrompt {
+	/// The prompt to use when none is given
+	const DEFAULT_PROMPT: &'static str;
+}
+
+macro_rules! implement_default_prompt {
+	($($type_base:ty),* => $default_prompt:expr) => {
+		$(
+			impl DefaultPrompt for $type_base {
+				const DEFAULT_PROMPT: &'static str = $default_prompt;
 			}
-		}
+		)*
+	};
+}
+
+implement_default_prompt!(char => "Enter a character: ");
+implement_default_prompt!(u8, u16, u32, u64, u128, usize => "Enter a number (positive integer): ");
+implement_default_prompt!(i8, i16, i32, i64, i128, isize => "Enter a number (integer): ");
+implement_default_prompt!(f32, f64 => "Enter a number: ");
+implement_default_prompt!(IpAddr => "Enter an IP address: ");
+implement_default_prompt!(Ipv4Addr => "Enter an IPv4 address: ");
+implement_default_prompt!(Ipv6Addr => "Enter an IPv6 address: ");
+implement_default_prompt!(SocketAddr => "Enter an address (like 127.0.0.1:8080): ");
+
+
+
+// these used to be separate structs, so each one is both a type alias and a constant, which lets them still be used like unit structs (`read!(U8Input)`, `impl Trait for U8Input`, etc)
+macro_rules! implement_parse_alias {
+	($type_name:ident, $type_base:ty) => {
+		#[allow(non_upper_case_globals)]
+		#[doc = concat!("Same as `Parse::<", stringify!($type_base), ">::new()`")]
+		pub const $type_name: $type_name = Parse::new();
 	};
 }
 
 /// Allows you take take a char input
-pub struct CharInput;
-implement_number_input!(CharInput, char, "Enter a character: ");
+pub type CharInput = Parse<char>;
+implement_parse_alias!(CharInput, char);
 
 /// Allows you take take a u8 input
-pub struct U8Input;
-implement_number_input!(U8Input, u8, "Enter a number (positive integer): ");
+pub type U8Input = Parse<u8>;
+implement_parse_alias!(U8Input, u8);
 
 /// Allows you take take an i8 input
-pub struct I8Input;
-implement_number_input!(I8Input, i8, "Enter a number (integer): ");
+pub type I8Input = Parse<i8>;
+implement_parse_alias!(I8Input, i8);
 
 /// Allows you take take a u16 input
-pub struct U16Input;
-implement_number_input!(U16Input, u16, "Enter a number (positive integer): ");
+pub type U16Input = Parse<u16>;
+implement_parse_alias!(U16Input, u16);
 
 /// Allows you take take an i16 input
-pub struct I16Input;
-implement_number_input!(I16Input, i16, "Enter a number (integer): ");
+pub type I16Input = Parse<i16>;
+implement_parse_alias!(I16Input, i16);
 
 /// Allows you take take a u32 input
-pub struct U32Input;
-implement_number_input!(U32Input, u32, "Enter a number (positive integer): ");
+pub type U32Input = Parse<u32>;
+implement_parse_alias!(U32Input, u32);
 
 /// Allows you take take an i32 input
-pub struct I32Input;
-implement_number_input!(I32Input, i32, "Enter a number (integer): ");
+pub type I32Input = Parse<i32>;
+implement_parse_alias!(I32Input, i32);
 
 /// Allows you take take a u64 input
-pub struct U64Input;
-implement_number_input!(U64Input, u64, "Enter a number (positive integer): ");
+pub type U64Input = Parse<u64>;
+implement_parse_alias!(U64Input, u64);
 
 /// Allows you take take an i64 input
-pub struct I64Input;
-implement_number_input!(I64Input, i64, "Enter a number (integer): ");
+pub type I64Input = Parse<i64>;
+implement_parse_alias!(I64Input, i64);
 
 /// Allows you take take a u128 input
-pub struct U128Input;
-implement_number_input!(U128Input, u128, "Enter a number (positive integer): ");
+pub type U128Input = Parse<u128>;
+implement_parse_alias!(U128Input, u128);
 
 /// Allows you take take an i128 input
-pub struct I128Input;
-implement_number_input!(I128Input, i128, "Enter a number (integer): ");
+pub type I128Input = Parse<i128>;
+implement_parse_alias!(I128Input, i128);
 
 /// Allows you take take a usize input
-pub struct UsizeInput;
-implement_number_input!(UsizeInput, usize, "Enter a number (positive integer): ");
+pub type UsizeInput = Parse<usize>;
+implement_parse_alias!(UsizeInput, usize);
 
 /// Allows you take take an isize input
-pub struct IsizeInput;
-implement_number_input!(IsizeInput, isize, "Enter a number (integer): ");
+pub type IsizeInput = Parse<isize>;
+implement_parse_alias!(IsizeInput, isize);
 
 /// Allows you take take an f32 input
-pub struct F32Input;
-implement_number_input!(F32Input, f32, "Enter a number: ");
+pub type F32Input = Parse<f32>;
+implement_parse_alias!(F32Input, f32);
 
 /// Allows you take take an f64 input
-pub struct F64Input;
-implement_number_input!(F64Input, f64, "Enter a number: ");
+pub type F64Input = Parse<f64>;
+implement_parse_alias!(F64Input, f64);
+
+/// Allows you to take a path input
+pub type PathInput = Parse<PathBuf>;
+/// Same as `Parse::<PathBuf>::with_renderer("Enter a path: ", ...)`
+#[allow(non_upper_case_globals)]
+pub const PathInput: PathInput = Parse::with_renderer("Enter a path: ", |path| path.display().to_string());
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use testing::ScriptedTerminal;
+	
+	#[test]
+	fn parse_errors_are_shown_and_retried() {
+		let script = ScriptedTerminal::new(&["localhost", "127.0.0.1"]);
+		let output = Parse::<IpAddr>::new().try_read_with(&mut script.terminal(), None, None);
+		assert_eq!(output.ok(), Some(IpAddr::from([127, 0, 0, 1])));
+		script.assert_shown("Enter an IP address: localhost\n");
+		script.assert_shown("Could not parse input (error: invalid IP address syntax)");
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn parse_uses_the_given_prompt() {
+		let script = ScriptedTerminal::new(&["x"]);
+		let output = Parse::<u8>::with_prompt("Enter a byte: ").try_read_with(&mut script.terminal(), None, None);
+		assert!(matches!(output, Err(SmartReadError::Eof)), "{output:?}");
+		script.assert_shown("Enter a byte: x\n");
+		script.assert_shown("Could not parse input (error: invalid digit found in string)");
+	}
+	
+	#[test]
+	fn path_defaults_are_shown() {
+		let script = ScriptedTerminal::new(&["", "out/log.txt"]);
+		let mut term = script.terminal();
+		let output = PathInput.try_read_with(&mut term, None, Some(PathBuf::from("log.txt")));
+		assert_eq!(output.ok(), Some(PathBuf::from("log.txt")));
+		script.assert_shown("Enter a path: (default: log.txt) \n");
+		let output = PathInput.try_read_with(&mut term, None, None);
+		assert_eq!(output.ok(), Some(PathBuf::from("out/log.txt")));
+	}
+}
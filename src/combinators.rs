@@ -0,0 +1,202 @@
+use crate::*;
+
+
+
+/// Adds combinators to every `TryRead` type
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// use smart_read::prelude::*;
+/// use smart_read::combinators::TryReadExt;
+/// 
+/// // uses the range's parsing and bounds checking, then checks that the number is a multiple of 5
+/// let input = read!((1..=100).validate(|n| n % 5 == 0));
+/// // converts the output to another type
+/// let seconds = prompt!("Minutes: "; U64Input.map(|minutes| minutes * 60));
+/// ```
+/// 
+/// NOTE: This trait isn't in the prelude or re-exported from the crate root because ranges are also iterators, so in files that use this trait, `Iterator::map()` on a range needs to be called as `Iterator::map(range, f)` (and `TryReadExt::map()` as `TryReadExt::map(range, f)`)
+pub trait TryReadExt: TryRead + Sized {
+	/// Converts the output with the given function
+	fn map<O, F: FnOnce(Self::Output) -> O>(self, f: F) -> Map<Self, F> {
+		Map {input: self, f}
+	}
+	/// Converts the output with the given function, and if it returns an error, shows the message and asks for another input
+	fn and_then<O, F: Fn(Self::Output) -> Result<O, String>>(self, f: F) -> AndThen<Self, F> {
+		AndThen {input: self, f}
+	}
+	/// Asks for another input until the output passes the given check
+	fn validate<F: Fn(&Self::Output) -> bool>(self, f: F) -> Validate<Self, F> {
+		Validate {input: self, f}
+	}
+	/// Uses the given function to create the default value, if no default value is given to the read
+	fn with_default_fn<F: FnOnce() -> Self::Default>(self, f: F) -> WithDefaultFn<Self, F> {
+		WithDefaultFn {input: self, f}
+	}
+}
+
+impl<T: TryRead> TryReadExt for T {}
+
+
+
+/// Created by `TryReadExt::map()`
+#[derive(Clone)]
+pub struct Map<T, F> {
+	input: T,
+	f: F,
+}
+
+impl<T, F, O> TryRead for Map<T, F>
+where
+	T: TryRead,
+	F: FnOnce(T::Output) -> O,
+{
+	type Output = O;
+	type Default = T::Default;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		let output = self.input.try_read_with(term, prompt, default)?;
+		Ok((self.f)(output))
+	}
+}
+
+
+
+/// Created by `TryReadExt::and_then()`
+/// 
+/// The inner input is read again after every error, so it has to implement `Clone`. Also, since the default value is given to the inner input, the retry policy's `OnRetriesExhausted::UseDefault` can't be used here
+/// 
+/// The default value is checked with the function before anything is read, and if it's rejected, this returns `SmartReadError::InvalidDefault`
+#[derive(Clone)]
+pub struct AndThen<T, F> {
+	input: T,
+	f: F,
+}
+
+impl<T, F, O> TryRead for AndThen<T, F>
+where
+	T: TryRead + Clone,
+	T::Default: Clone,
+	F: Fn(T::Output) -> Result<O, String>,
+{
+	type Output = O;
+	type Default = T::Default;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		// otherwise, a rejected default would be given back to the inner input on every retry (and forever in non-interactive mode)
+		if let Some(default) = &default {
+			let output = default_output(self.input.clone(), default.clone())?;
+			if let Err(error_message) = (self.f)(output) {
+				return Err(SmartReadError::InvalidDefault(error_message));
+			}
+		}
+		let mut retries = RetryCounter::new(term);
+		loop {
+			
+			let output = self.input.clone().try_read_with(term, prompt.clone(), default.clone())?;
+			match (self.f)(output) {
+				Ok(output) => return Ok(output),
+				Err(error_message) => {
+					retries.invalid_input(term, &error_message, &mut None::<O>)?;
+				}
+			}
+			
+		}
+	}
+}
+
+
+
+/// Created by `TryReadExt::validate()`
+/// 
+/// The inner input is read again after every invalid output, so it has to implement `Clone`. Also, since the default value is given to the inner input, the retry policy's `OnRetriesExhausted::UseDefault` can't be used here
+/// 
+/// The default value is checked before anything is read, and if it doesn't pass, this returns `SmartReadError::InvalidDefault`
+#[derive(Clone)]
+pub struct Validate<T, F> {
+	input: T,
+	f: F,
+}
+
+impl<T, F> TryRead for Validate<T, F>
+where
+	T: TryRead + Clone,
+	T::Default: Clone,
+	F: Fn(&T::Output) -> bool,
+{
+	type Output = T::Output;
+	type Default = T::Default;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		let f = self.f;
+		AndThen {
+			input: self.input,
+			f: |output| if f(&output) {Ok(output)} else {Err(String::from("Invalid input"))},
+		}.try_read_with(term, prompt, default)
+	}
+}
+
+
+
+// reads the default value with the inner input, without asking for anything or showing anything
+fn default_output<T: TryRead>(input: T, default: T::Default) -> ReadResult<T::Output> {
+	let mut term = Terminal::new(std::io::empty(), std::io::sink());
+	term.non_interactive = Some(true);
+	input.try_read_with(&mut term, None, Some(default))
+}
+
+
+
+/// Created by `TryReadExt::with_default_fn()`
+#[derive(Clone)]
+pub struct WithDefaultFn<T, F> {
+	input: T,
+	f: F,
+}
+
+impl<T, F> TryRead for WithDefaultFn<T, F>
+where
+	T: TryRead,
+	F: FnOnce() -> T::Default,
+{
+	type Output = T::Output;
+	type Default = T::Default;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		let default = Some(default.unwrap_or_else(self.f));
+		self.input.try_read_with(term, prompt, default)
+	}
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use testing::ScriptedTerminal;
+	
+	#[test]
+	fn rejected_default_is_an_error() {
+		let script = ScriptedTerminal::new::<&str>(&[]);
+		let mut term = script.terminal();
+		term.non_interactive = Some(true);
+		let output = Prompt::new("n: ").default(3).try_read_with(&mut term, (1..=100).validate(|n| n % 5 == 0));
+		assert!(matches!(output, Err(SmartReadError::InvalidDefault(_))), "{output:?}");
+		script.assert_not_shown("Invalid input");
+	}
+	
+	#[test]
+	fn accepted_default_is_used() {
+		let script = ScriptedTerminal::new::<&str>(&[]);
+		let mut term = script.terminal();
+		term.non_interactive = Some(true);
+		let output = Prompt::new("n: ").default(5).try_read_with(&mut term, (1..=100).validate(|n| n % 5 == 0));
+		assert_eq!(output.ok(), Some(5));
+	}
+	
+	#[test]
+	fn invalid_output_is_asked_for_again() {
+		let script = ScriptedTerminal::new(&["3", "", "10"]);
+		let output = Prompt::new("n: ").default(5).try_read_with(&mut script.terminal(), (1..=100).and_then(|n| if n % 5 == 0 {Ok(n / 5)} else {Err(String::from("Not a multiple of 5"))}));
+		assert_eq!(output.ok(), Some(1));
+		script.assert_shown("Not a multiple of 5");
+	}
+}
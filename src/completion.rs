@@ -0,0 +1,119 @@
+/// Suggests completions for what's been typed, which `LineEditor` uses when Tab is pressed
+/// 
+/// Pressing Tab once fills in the text that all completions start with, and pressing it again lists every completion. Completers can be given to any prompt with the `completer` option, and lists of `InputOption`s complete their names automatically
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// # use smart_read::prelude::*;
+/// let hosts = vec!("web-1.example.com", "web-2.example.com", "db.example.com");
+/// let host = prompt!("Host: "; completer = hosts; SimpleValidate(|input| if input.contains(' ') {Err(String::from("Invalid input, must not contain spaces"))} else {Ok(())}));
+/// let file = prompt!("File: "; completer = PathCompleter; TransformValidate(|input| std::fs::read_to_string(&input).map_err(|err| err.to_string())));
+/// ```
+pub trait Completer {
+	/// Returns every line that the typed text could be completed to
+	fn complete(&self, input: &str) -> Vec<String>;
+}
+
+impl<F: Fn(&str) -> Vec<String>> Completer for F {
+	fn complete(&self, input: &str) -> Vec<String> {
+		self(input)
+	}
+}
+
+/// Completes to every value that starts with the typed text, ignoring ascii case
+impl<T: AsRef<str>> Completer for Vec<T> {
+	fn complete(&self, input: &str) -> Vec<String> {
+		self.iter()
+			.map(AsRef::as_ref)
+			.filter(|value| value.get(..input.len()).is_some_and(|start| start.eq_ignore_ascii_case(input)))
+			.map(String::from)
+			.collect()
+	}
+}
+
+
+
+/// Completes file and folder paths, folders end with `/`
+/// 
+/// Hidden files (the ones starting with `.`) are only completed once a `.` is typed
+#[derive(Clone, Copy, Default)]
+pub struct PathCompleter;
+
+impl Completer for PathCompleter {
+	fn complete(&self, input: &str) -> Vec<String> {
+		let (dir, name_start) = match input.rfind(['/', std::path::MAIN_SEPARATOR]) {
+			Some(i) => input.split_at(i + 1),
+			None => ("", input),
+		};
+		let Ok(entries) = std::fs::read_dir(if dir.is_empty() {"."} else {dir}) else {return vec!();};
+		let mut completions = entries
+			.filter_map(|entry| {
+				let entry = entry.ok()?;
+				let name = entry.file_name().into_string().ok()?;
+				if !name.starts_with(name_start) || (name.starts_with('.') && !name_start.starts_with('.')) {return None;}
+				let slash = if entry.path().is_dir() {"/"} else {""};
+				Some(format!("{dir}{name}{slash}"))
+			})
+			.collect::<Vec<_>>();
+		completions.sort();
+		completions
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+	use std::rc::Rc;
+	
+	#[test]
+	fn values_are_completed_ignoring_case() {
+		let hosts = vec!("web-1.example.com", "Web-2.example.com", "db.example.com");
+		assert_eq!(hosts.complete("WEB"), ["web-1.example.com", "Web-2.example.com"]);
+		assert_eq!(hosts.complete(""), hosts);
+		assert!(hosts.complete("x").is_empty());
+		// a prefix that ends inside a character doesn't match
+		assert!(vec!("é").complete("a").is_empty());
+	}
+	
+	#[test]
+	fn paths_are_completed() {
+		let dir = std::env::temp_dir().join(format!("smart-read-completion-test-{}", std::process::id()));
+		let _ = std::fs::create_dir_all(dir.join("alps"));
+		let _ = std::fs::write(dir.join("alpha.txt"), "");
+		let _ = std::fs::write(dir.join(".alpine"), "");
+		let dir_name = format!("{}/", dir.display());
+		let completions = PathCompleter.complete(&format!("{dir_name}al"));
+		let hidden_completions = PathCompleter.complete(&format!("{dir_name}.al"));
+		let _ = std::fs::remove_dir_all(&dir);
+		assert_eq!(completions, [format!("{dir_name}alpha.txt"), format!("{dir_name}alps/")]);
+		assert_eq!(hidden_completions, [format!("{dir_name}.alpine")]);
+		assert!(PathCompleter.complete(&format!("{dir_name}al")).is_empty());
+	}
+	
+	// gives each line the first completion of "gr", like typing "gr" and pressing Tab
+	#[derive(Default)]
+	struct CompletingInput(Option<Rc<dyn Completer>>);
+	
+	impl InputSource for CompletingInput {
+		fn next_line(&mut self) -> Result<Option<String>, std::io::Error> {
+			Ok(self.0.as_ref().and_then(|completer| completer.complete("gr").into_iter().next()))
+		}
+		fn set_completer(&mut self, completer: Option<Rc<dyn Completer>>) {
+			self.0 = completer;
+		}
+	}
+	
+	#[test]
+	fn list_option_names_are_completed() {
+		let mut term = Terminal::new(CompletingInput::default(), std::io::sink());
+		let output = ["red", "green", "blue"].try_read_with(&mut term, None, None);
+		assert_eq!(output.ok(), Some((1, "green")));
+		assert!(term.completer.is_none());
+		let output = Prompt::empty().completer(vec!("grey")).try_read_with(&mut term, ());
+		assert_eq!(output.ok().as_deref(), Some("grey"));
+	}
+}
@@ -0,0 +1,187 @@
+use crate::*;
+use recording::{escape, unescape};
+use std::{collections::BTreeMap, path::PathBuf, rc::Rc};
+
+
+
+/// Earlier answers for each prompt, which `LineEditor` lets users recall with the Up and Down arrows or search with Ctrl-R
+/// 
+/// Answers are grouped by the prompt's id (see `Terminal::prompt_id`), or by the first line of the prompt if it has no id. Entering an answer again moves it to the end instead of adding a duplicate, and only the newest `max_len` answers of each prompt are kept. Secrets (see `SecretInput`) are never added
+/// 
+/// By default the history is only kept in memory, but it can also be saved to a file after every change:
+/// 
+/// ```no_run
+/// use smart_read::prelude::*;
+/// 
+/// let history = History::in_data_dir("my-app").unwrap();  // `~/.local/share/my-app/history.txt` on Linux
+/// set_default_terminal(LineEditor::new().with_history(history).terminal());
+/// let host = prompt!("Host: "; id = "host"; NonEmptyInput);
+/// ```
+/// 
+/// Clones of a `History` share the same answers
+#[derive(Clone)]
+pub struct History {
+	state: Rc<RefCell<HistoryState>>,
+}
+
+struct HistoryState {
+	// the answers for each prompt, from oldest to newest
+	entries: BTreeMap<String, Vec<String>>,
+	max_len: usize,
+	path: Option<PathBuf>,
+}
+
+impl Default for History {
+	fn default() -> Self {
+		Self {
+			state: Rc::new(RefCell::new(HistoryState {
+				entries: BTreeMap::new(),
+				max_len: Self::DEFAULT_MAX_LEN,
+				path: None,
+			})),
+		}
+	}
+}
+
+impl History {
+	/// How many answers are kept for each prompt, unless `max_len()` is used
+	pub const DEFAULT_MAX_LEN: usize = 500;
+	/// Basic initializer, the history is only kept in memory
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Loads the history from a file (if it exists), and saves it to the file after every change
+	pub fn load(path: impl Into<PathBuf>) -> ReadResult<Self> {
+		let path = path.into();
+		let history = Self::new();
+		match std::fs::read_to_string(&path) {
+			Ok(text) => history.add_text(&text),
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+			Err(err) => return Err(err.into()),
+		}
+		history.state.borrow_mut().path = Some(path);
+		Ok(history)
+	}
+	/// Same as `load()`, with the file `history.txt` in a folder named `app_name` in the user's data directory
+	/// 
+	/// The data directory is `$XDG_DATA_HOME` or `~/.local/share` on Linux, `~/Library/Application Support` on macOS, and `%APPDATA%` on Windows
+	pub fn in_data_dir(app_name: &str) -> ReadResult<Self> {
+		let Some(data_dir) = data_dir() else {
+			return Err(SmartReadError::Other("could not find the user's data directory".into()));
+		};
+		Self::load(data_dir.join(app_name).join("history.txt"))
+	}
+	/// Sets how many answers are kept for each prompt, older answers are removed first
+	pub fn max_len(self, max_len: usize) -> Self {
+		let mut state = self.state.borrow_mut();
+		state.max_len = max_len;
+		for entries in state.entries.values_mut() {
+			let extra_len = entries.len().saturating_sub(max_len);
+			entries.drain(..extra_len);
+		}
+		drop(state);
+		self
+	}
+	/// Returns the answers for the given prompt id, from oldest to newest
+	pub fn entries(&self, key: &str) -> Vec<String> {
+		self.state.borrow().entries.get(key).cloned().unwrap_or_default()
+	}
+	/// Adds an answer for the given prompt id, then saves the history if it was loaded from a file
+	/// 
+	/// Blank answers aren't added
+	pub fn add(&self, key: &str, entry: &str) -> ReadResult<()> {
+		if entry.trim().is_empty() {return Ok(());}
+		self.add_entry(key, entry);
+		self.save()
+	}
+	/// Converts the history to text, with one answer per line
+	pub fn to_text(&self) -> String {
+		let state = self.state.borrow();
+		let mut output = String::new();
+		for (key, entries) in &state.entries {
+			for entry in entries {
+				output += &format!("{}\t{}\n", escape(key), escape(entry));
+			}
+		}
+		output
+	}
+	/// Writes the history to the file it was loaded from, if any
+	pub fn save(&self) -> ReadResult<()> {
+		let Some(path) = self.state.borrow().path.clone() else {return Ok(());};
+		if let Some(dir) = path.parent() {
+			std::fs::create_dir_all(dir)?;
+		}
+		std::fs::write(path, self.to_text())?;
+		Ok(())
+	}
+	fn add_entry(&self, key: &str, entry: &str) {
+		let mut state = self.state.borrow_mut();
+		let max_len = state.max_len;
+		let entries = state.entries.entry(key.to_string()).or_default();
+		entries.retain(|existing_entry| existing_entry != entry);
+		entries.push(entry.to_string());
+		let extra_len = entries.len().saturating_sub(max_len);
+		entries.drain(..extra_len);
+	}
+	// lines that can't be parsed are skipped, so that a damaged file doesn't stop the program
+	fn add_text(&self, text: &str) {
+		for line in text.lines() {
+			let Some((key, entry)) = line.split_once('\t') else {continue;};
+			self.add_entry(&unescape(key), &unescape(entry));
+		}
+	}
+}
+
+fn data_dir() -> Option<PathBuf> {
+	let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from).filter(|dir| dir.is_absolute());
+	if cfg!(windows) {
+		return env_dir("APPDATA");
+	}
+	if cfg!(target_os = "macos") {
+		return env_dir("HOME").map(|home| home.join("Library").join("Application Support"));
+	}
+	env_dir("XDG_DATA_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".local").join("share")))
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn entries_are_deduplicated_and_capped() {
+		let history = History::new().max_len(3);
+		for entry in ["a", "b", "a", " ", "c", "d"] {
+			assert!(history.add("host", entry).is_ok());
+		}
+		assert!(history.add("port", "80").is_ok());
+		assert_eq!(history.entries("host"), ["a", "c", "d"]);
+		assert_eq!(history.entries("port"), ["80"]);
+		assert!(history.entries("other").is_empty());
+		let history = history.max_len(1);
+		assert_eq!(history.entries("host"), ["d"]);
+	}
+	
+	#[test]
+	fn history_is_saved_and_loaded() {
+		let path = std::env::temp_dir().join(format!("smart-read-history-test-{}", std::process::id())).join("history.txt");
+		let _ = std::fs::remove_file(&path);
+		let history = History::load(&path);
+		assert!(history.as_ref().is_ok_and(|history| history.entries("host").is_empty()));
+		for entry in ["one", "tab\there", "line\nbreak", "one"] {
+			assert!(history.as_ref().is_ok_and(|history| history.add("key\twith\ttabs", entry).is_ok()));
+		}
+		let loaded = History::load(&path);
+		let _ = std::fs::remove_dir_all(path.parent().unwrap_or(&path));
+		assert_eq!(loaded.ok().map(|history| history.entries("key\twith\ttabs")), Some(vec!(String::from("tab\there"), String::from("line\nbreak"), String::from("one"))));
+	}
+	
+	#[test]
+	fn damaged_lines_are_skipped() {
+		let history = History::new();
+		history.add_text("host\ta\nnot a valid line\nhost\tb\n");
+		assert_eq!(history.entries("host"), ["a", "b"]);
+	}
+}
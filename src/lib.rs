@@ -15,18 +15,21 @@
 //! 
 //! ### Basics
 //! 
-//! ```
+//! ```ignore
 //! impl TryRead for ()                  // requests any string from the user
 //! impl TryRead for NonEmptyInput       // requests a non-empty string from the user
 //! impl TryRead for NonWhitespaceInput  // requests a non-whitespace string from the user
 //! impl TryRead for BoolInput           // requests a true/false/t/f string from the user
 //! impl TryRead for YesNoInput          // requests a yes/no/y/n string from the user
+//! // requests a string from the user that can be parsed into `T` (numbers, `IpAddr`, `SocketAddr`, your own types, etc):
+//! impl<T: FromStr> TryRead for Parse<T> where <T as FromStr>::Err: Display
+//! // these are aliases for `Parse<T>`:
 //! impl TryRead for CharInput           // requests a single-char string from the user
-//! // requests a number from the user that can be converted to a specific type:
-//! impl TryRead for U8Input, U16Input, U32Input, U64Input, U128Input, USizeInput
-//! impl TryRead for I8Input, I16Input, I32Input, I64Input, I128Input, ISizeInput
+//! impl TryRead for U8Input, U16Input, U32Input, U64Input, U128Input, UsizeInput
+//! impl TryRead for I8Input, I16Input, I32Input, I64Input, I128Input, IsizeInput
 //! impl TryRead for F32Input
 //! impl TryRead for F64Input
+//! impl TryRead for PathInput
 //! ```
 //! 
 //! <br>
@@ -35,7 +38,7 @@
 //! 
 //! These allow you to easily add custom logic to specific reads
 //! 
-//! ```
+//! ```ignore
 //! // requests a string from the user which passes the programmed validation:
 //! impl<F> TryRead for SimpleValidate<F> where F: Fn(&str) -> Result<(), String>
 //! // similar to `SimpleValidate`, but also transforms the output:
@@ -50,7 +53,7 @@
 //! 
 //! NOTE: The default value for these types denotes the index of the default option
 //! 
-//! ```
+//! ```ignore
 //! // requests a string from the user that matches any of the names from the `InputOption`s:
 //! impl<Data> TryRead for &[InputOption<Data>]
 //! impl<Data> TryRead for &[InputOption<Data>; N]
@@ -69,7 +72,7 @@
 //! 
 //! These allow you to take a number within a specified range. Example: `read!(1. ..= 100.)`, `read!(10..)`, etc
 //! 
-//! ```
+//! ```ignore
 //! impl<T> TryRead for Range<T>            where T: Display + FromStr + PartialOrd<T>, <T as FromStr>::Err: Display
 //! impl<T> TryRead for RangeInclusive<T>   where T: Display + FromStr + PartialOrd<T>, <T as FromStr>::Err: Display
 //! impl<T> TryRead for RangeTo<T>          where T: Display + FromStr + PartialOrd<T>, <T as FromStr>::Err: Display
@@ -78,21 +81,86 @@
 //! ```
 //! 
 //! <br>
+//! 
+//! ### Tuples
+//! 
+//! These allow you to take multiple values from a single line. Example: `read!((I32Input, I32Input))`
+//! 
+//! ```ignore
+//! // requests values separated by whitespace, each one is parsed and validated by its own type:
+//! impl<A: PromptHooks, B: PromptHooks, ...> TryRead for (A, B, ...)  // for 2 to 6 values
+//! // same as above, but the values are separated by the given string:
+//! impl<T: TupleInput> TryRead for Separated<T>
+//! ```
+//! 
+//! <br>
+//! 
+//! ### List Of
+//! 
+//! This allows you to take a list of values from a single line. Example: `read!(ListOf::new(1..=65535))`
+//! 
+//! ```ignore
+//! // requests values separated by "," (or any other separator), each one is parsed and validated by the element type:
+//! impl<T: PromptHooks> TryRead for ListOf<T>
+//! ```
+//! 
+//! <br>
+//! 
+//! ### Repeat Until
+//! 
+//! This allows you to take any number of values, one per line, until a blank line is entered. Example: `read!(RepeatUntil::new(NonEmptyInput))`
+//! 
+//! ```ignore
+//! // requests values until a blank line is entered, each one is parsed and validated by the element type:
+//! impl<T: PromptHooks> TryRead for RepeatUntil<T>
+//! ```
+//! 
+//! <br>
+//! 
+//! ### Optional
+//! 
+//! This allows any input other than lists to be skipped. Example: `read!(Optional(UsizeInput))`
+//! 
+//! ```ignore
+//! // requests an input of the inner type, or nothing to get `None`:
+//! impl<T: PromptHooks> TryRead for Optional<T>
+//! ```
+//! 
+//! <br>
+//! 
+//! ### Secret Input
+//! 
+//! This allows you to read passwords without showing what's typed. Example: `read!(SecretInput::new().min_len(8).confirm("Confirm password: "))`
+//! 
+//! ```ignore
+//! // requests a secret with the terminal's echo turned off, optionally twice, and gives a `SecretString` that's wiped from memory when dropped:
+//! impl TryRead for SecretInput
+//! ```
+//! 
+//! <br>
 //! <br>
 //! 
 //! # Macro Syntax
 //! 
-//! Prompt macros: &nbsp; `prompt!("message to user"; [default_value] input_type)`
+//! Prompt macros: &nbsp; `prompt!("message to user"; option = value; [default_value] input_type)`
 //! 
-//! Read macros: &nbsp; `read!([default_value] input_type)`
+//! Read macros: &nbsp; `read!(option = value; [default_value] input_type)`
 //! 
-//! All components are optional (except the message in prompts) and all are expressions.
+//! All components are optional (except the message in prompts) and all are expressions. Options can be given any number of times, and each one sets a field of the `Terminal` for just this read (for example, `retry_policy = RetryPolicy::max_attempts(3);`).
 //! 
 //! Some examples:
-//! ```
+//! ```ignore
 //! read!([2] 1..=10);  // take a number from 1 to 10, with 2 as the default
 //! prompt!(messages[i]; UsizeInput);  // request a positive integer for the current prompt
 //! prompt!("continue?"; [true] YesNoInput);  // request a yes/no input with yes being the default
+//! prompt!("PIN: "; retry_policy = RetryPolicy::max_attempts(3); U32Input);  // give up after 3 invalid inputs
+//! prompt!("continue?"; timeout = Duration::from_secs(30); [true] YesNoInput);  // use the default if nothing is entered within 30 seconds
+//! prompt!("Port: "; prompt_id = "port"; 1..=65535);  // can be answered ahead of time with `SMART_READ_ANSWER_PORT=8080`
+//! ```
+//! 
+//! The macros are shorthand for `Prompt`, a builder that can also be used directly. Each option is a method of `Prompt`, so `prompt!("Port: "; prompt_id = "port"; [8080] 1..=65535)` is the same as:
+//! ```ignore
+//! Prompt::new("Port: ").prompt_id("port").default(8080).read(1..=65535)
 //! ```
 //! 
 //! <br>
@@ -101,7 +169,7 @@
 //! 
 //! Some input types have special syntax that can be substituted for the input_type component, they are:
 //! 
-//! ```
+//! ```ignore
 //! // this:
 //! read!()
 //! // is this:
@@ -124,6 +192,11 @@
 //! 	InputOption::new("2_bulletin", vec!("2_display_name", "2_alt_name_1", ...), 2_data),
 //! 	...
 //! ])
+//! 
+//! // this:
+//! read!(scan "{}x{}")
+//! // is this:
+//! read!(Scan::new("{}x{}"))
 //! ```
 //! 
 //! <br>
@@ -132,6 +205,27 @@
 //! 
 //! <br>
 //! 
+//! # Input and Output
+//! 
+//! By default, everything is read from stdin and written to stdout, but a `Terminal` can be made from any `InputSource` (anything that implements `BufRead`) and any `OutputSink` (anything that implements `Write`):
+//! 
+//! ```ignore
+//! // use a terminal for a single read:
+//! let mut term = Terminal::new(BufReader::new(stream.try_clone()?), stream);
+//! let input = UsizeInput.try_read_with(&mut term, Some(String::from("Enter a number: ")), None)?;
+//! 
+//! // or use it for all `read!()` and `prompt!()` calls on the current thread:
+//! set_default_terminal(Terminal::new(BufReader::new(stream.try_clone()?), stream));
+//! ```
+//! 
+//! When stdin isn't a terminal (for example, in CI or cron jobs), reads are non-interactive: they use their default value right away, or return `SmartReadError::NonInteractive` if there is no default. This can also be forced on or off with `set_non_interactive()` or the `SMART_READ_NON_INTERACTIVE` environment variable, see `Terminal::is_non_interactive()` for more
+//! 
+//! To give users cursor movement and editing keys (like Ctrl-A, Ctrl-W, and the arrow keys) while they type, use `set_default_terminal(LineEditor::new().terminal())`, see `LineEditor` for more. The line editor also lets users recall earlier answers to the same prompt with the Up arrow or Ctrl-R, and these can be saved between runs with `History::in_data_dir()`. Pressing Tab completes the names of `InputOption`s, and any prompt can suggest its own completions with a `Completer` (like `PathCompleter`)
+//! 
+//! For testing code that reads user input, `testing::ScriptedTerminal` can give pre-written answers and check what was shown to the user
+//! 
+//! <br>
+//! 
 //! If you have ideas for more functionality (including things you've found to be useful yourself), feel free to open an issue / pull request
 //! 
 //! <br>
@@ -139,11 +233,16 @@
 
 
 
-#![feature(let_chains)]
 #![allow(clippy::tabs_in_doc_comments, clippy::neg_multiply)]
 #![warn(missing_docs, clippy::todo, clippy::unwrap_used, clippy::panic, clippy::expect_used)]
 
-use std::{error::Error, fmt::{Debug, Display}, io::Write};
+pub use prompt_builder::Prompt;
+use prompt_builder::IntoDefault;
+use preset_answers::{AnswerFile, PresetAnswer};
+use recording::{SessionEvent, SessionRecorder, SessionReplay};
+use secret::{NonTtyPolicy, SecretBytes, SecretString};
+use completion::Completer;
+use std::{cell::{Cell, RefCell}, rc::Rc, error::Error, fmt::{Debug, Display}, io::{BufRead, IsTerminal, Write}, sync::{Mutex, PoisonError}, time::Duration};
 
 
 
@@ -155,6 +254,35 @@ pub mod input_validation;
 pub mod list_constraints;
 /// Contains implementations for `Range<T>`, `RangeFrom<T>`, etc
 pub mod range_constraints;
+/// Contains implementations for tuples, which read multiple values from one line
+pub mod tuples;
+/// Contains `ListOf`, which reads a list of values from one line
+pub mod list_of;
+/// Contains `RepeatUntil`, which reads values one line at a time until a blank line is entered
+pub mod repeat_until;
+/// Contains `Scan`, which reads values from a line that matches a template
+pub mod scan;
+/// Contains `Optional`, for inputs that can be skipped
+pub mod optional;
+/// Contains `SecretInput` and `SecretString`, for reading passwords without showing or keeping them
+pub mod secret;
+/// Contains `TryReadExt`, which adds `map()`, `and_then()`, `validate()`, and `with_default_fn()` to every `TryRead` type
+pub mod combinators;
+/// Contains `Prompt`, a builder that can be used instead of the `read!()` and `prompt!()` macros
+pub mod prompt_builder;
+/// Contains `LineEditor`, which adds cursor movement and editing keys to stdin
+pub mod line_editor;
+/// Contains `History`, which keeps earlier answers for `LineEditor`
+pub mod history;
+/// Contains `Completer` and `PathCompleter`, which suggest completions when Tab is pressed in `LineEditor`
+pub mod completion;
+/// Contains `AnswerFile`, `PresetAnswer`, and the functions for giving answers through environment variables
+pub mod preset_answers;
+/// Contains `SessionRecorder` and `SessionReplay`, for recording and replaying interactive sessions
+pub mod recording;
+/// Contains `ScriptedTerminal`, for testing code that reads user input
+pub mod testing;
+mod tty;
 
 /// Easy way to use existing functionality. If you want to extend functionality instead, you can do `use smart_read::*;`
 pub mod prelude {
@@ -164,10 +292,30 @@ pub mod prelude {
 		prompt,
 		try_prompt,
 		wait_for_enter,
+		Prompt,
+		set_default_terminal,
+		set_default_retry_policy,
+		set_non_interactive,
+		Terminal,
+		Prompter,
+		Theme,
+		SmartReadError,
+		RetryPolicy,
 		basics::*,
 		input_validation::*,
 		list_constraints::*,
 		range_constraints::*,
+		tuples::Separated,
+		scan::Scan,
+		list_of::ListOf,
+		repeat_until::RepeatUntil,
+		optional::Optional,
+		secret::{SecretInput, SecretString, NonTtyPolicy},
+		line_editor::LineEditor,
+		history::History,
+		completion::{Completer, PathCompleter},
+		preset_answers::AnswerFile,
+		recording::{SessionRecorder, SessionReplay},
 	};
 }
 
@@ -187,11 +335,11 @@ macro_rules! read {
 	}
 }
 
-/// Same as `read!()`, but returns a result
+/// Same as `read!()`, but returns a `ReadResult`, so the reason for a failed read can be checked with `SmartReadError`
 #[macro_export]
 macro_rules! try_read {
 	($($args:tt)*) => {
-		smart_read::run_with_prompt!(None; $($args)*)
+		smart_read::run_with_prompt!(smart_read::Prompt::empty(); $($args)*)
 	};
 }
 
@@ -205,14 +353,14 @@ macro_rules! prompt {
 	}
 }
 
-/// Same as `prompt!()`, but returns a result
+/// Same as `prompt!()`, but returns a `ReadResult`, so the reason for a failed read can be checked with `SmartReadError`
 #[macro_export]
 macro_rules! try_prompt {
 	($prompt:expr) => {
-		smart_read::run_with_prompt!(Some($prompt.to_string());)
+		smart_read::run_with_prompt!(smart_read::Prompt::new($prompt.to_string());)
 	};
 	($prompt:expr; $($args:tt)*) => {
-		smart_read::run_with_prompt!(Some($prompt.to_string()); $($args)*)
+		smart_read::run_with_prompt!(smart_read::Prompt::new($prompt.to_string()); $($args)*)
 	};
 }
 
@@ -221,11 +369,14 @@ macro_rules! try_prompt {
 #[macro_export]
 #[doc(hidden)]
 macro_rules! run_with_prompt {
-	($prompt:expr; [$default:expr] $($args:tt)*) => {
-		smart_read::run_with_prompt_and_default!($prompt; Some($default.into()); $($args)*)
+	($builder:expr; $option:ident = $value:expr; $($args:tt)*) => {
+		smart_read::run_with_prompt!($builder.$option($value); $($args)*)
 	};
-	($prompt:expr; $($args:tt)*) => {
-		smart_read::run_with_prompt_and_default!($prompt; None; $($args)*)
+	($builder:expr; [$default:expr] $($args:tt)*) => {
+		smart_read::run_with_prompt_and_default!($builder.default($default); $($args)*)
+	};
+	($builder:expr; $($args:tt)*) => {
+		smart_read::run_with_prompt_and_default!($builder; $($args)*)
 	};
 }
 
@@ -235,24 +386,29 @@ macro_rules! run_with_prompt {
 #[doc(hidden)]
 macro_rules! run_with_prompt_and_default {
 	
-	($prompt:expr; $default:expr;) => {{
+	($builder:expr;) => {{
+		use smart_read::TryRead;
+		().try_read_using($builder)
+	}};
+	
+	($builder:expr; = $($option_bulletin:expr; $option_name:expr; [$($option_alt:expr),*]; $option_data:expr,)*) => {{
 		use smart_read::TryRead;
-		().try_read_line($prompt, $default)
+		[$(InputOption::new($option_bulletin, &[$option_name.to_string() $(,$option_alt.to_string())*], $option_data)),*].try_read_using($builder)
 	}};
 	
-	($prompt:expr; $default:expr; = $($option_bulletin:expr; $option_name:expr; [$($option_alt:expr),*]; $option_data:expr,)*) => {{
+	($builder:expr; = $($option:expr),*) => {{
 		use smart_read::TryRead;
-		[$(InputOption::new($option_bulletin, &[$option_name.to_string() $(,$option_alt.to_string())*], $option_data)),*].try_read_line($prompt, $default)
+		[$($option),*].try_read_using($builder)
 	}};
 	
-	($prompt:expr; $default:expr; = $($option:expr),*) => {{
+	($builder:expr; scan $template:expr) => {{
 		use smart_read::TryRead;
-		[$($option),*].try_read_line($prompt, $default)
+		smart_read::scan::Scan::new($template).try_read_using($builder)
 	}};
 	
-	($prompt:expr; $default:expr; $tryread_type:expr) => {{
+	($builder:expr; $tryread_type:expr) => {{
 		use smart_read::TryRead;
-		($tryread_type).try_read_line($prompt, $default)
+		($tryread_type).try_read_using($builder)
 	}};
 	
 }
@@ -265,19 +421,887 @@ macro_rules! run_with_prompt_and_default {
 
 
 
-/// Just `Result<T, Box<dyn Error>>`, mostly for internal use
+/// Just `Result<T, SmartReadError>`, this is what every `TryRead` impl returns
+pub type ReadResult<T> = Result<T, SmartReadError>;
+
+/// Just `Result<T, Box<dyn Error>>`, this is what `TryRead` impls used to return
+#[deprecated(since = "0.9.0", note = "use `ReadResult` instead, this will be removed in the next release")]
 pub type BoxResult<T> = Result<T, Box<dyn Error>>;
 
 
 
+/// Every way that reading an input can fail
+/// 
+/// This is `Send` and `Sync`, so it can be converted to `Box<dyn Error + Send + Sync>` (or `anyhow::Error`) with `?`:
+/// 
+/// ```no_run
+/// use smart_read::prelude::*;
+/// 
+/// fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// 	let port = try_prompt!("Port: "; 1..=65535)?;
+/// 	std::thread::spawn(move || println!("Listening on port {port}"));
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub enum SmartReadError {
+	/// Reading from the input or writing to the output failed
+	Io(std::io::Error),
+	/// There is no more input to read, for example when stdin is closed
+	/// 
+	/// Without this, inputs that keep asking until they get a valid input would loop forever
+	Eof,
+	/// The read was interrupted, for example by Ctrl-C
+	Interrupted,
+	/// The read was cancelled before an input was chosen, for example by pressing Esc in a `LineEditor`
+	/// 
+	/// An `InputSource` can cancel a read by returning `std::io::Error::other(SmartReadError::Cancelled)`
+	Cancelled,
+	/// No input was given before the terminal's timeout, and there was no default value to use instead
+	Timeout,
+	/// The terminal is non-interactive (see `Terminal::is_non_interactive()`) and there was no default value to use instead
+	NonInteractive {
+		/// The first line of the prompt that needed an input
+		prompt: String,
+	},
+	/// Too many invalid inputs were given
+	RetriesExhausted {
+		/// The number of inputs that were tried
+		attempts: usize,
+	},
+	/// The given default value cannot be used, the string explains why
+	InvalidDefault(String),
+	/// A preset answer (see `Terminal::prompt_id`) was not a valid input
+	InvalidAnswer {
+		/// Where the answer came from, for example `SMART_READ_ANSWER_DEPLOY_TARGET`
+		source: String,
+		/// The answer that was given
+		answer: String,
+		/// The error message that would have been shown to the user
+		reason: String,
+	},
+	/// An answer file could not be parsed or had answers that were never used, the string explains why
+	InvalidAnswerFile(String),
+	/// A replayed session (see `Terminal::replay`) didn't match what the program asked for
+	ReplayDiverged {
+		/// Which step of the recording didn't match, starting at 1 (this is also the line number in a saved recording)
+		step: usize,
+		/// What the recording has at this step
+		expected: String,
+		/// What the program did instead
+		found: String,
+	},
+	/// The terminal has an answer file (see `Terminal::answer_file`) that doesn't have an answer for a prompt, and it doesn't allow falling back to typed input
+	MissingAnswer {
+		/// The prompt's id, or the first line of the prompt if it has no id
+		prompt: String,
+	},
+	/// A list of options was empty, which would be a softlock
+	EmptyList,
+	/// A secret input (see `SecretInput`) couldn't be read without showing it, and its `NonTtyPolicy` is `Refuse`
+	CannotHideInput,
+	/// `read!()`, `prompt!()`, etc were used while the default terminal was already being used by another read on the same thread, for example in a `TryRead` impl (which should read through the terminal that it's given instead, see `Terminal::try_prompt()`)
+	DefaultTerminalInUse,
+	/// Any other error, mostly for custom `TryRead` impls
+	Other(Box<dyn Error + Send + Sync>),
+}
+
+impl Error for SmartReadError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::Io(err) => Some(err),
+			Self::Other(err) => Some(&**err),
+			_ => None,
+		}
+	}
+}
+
+impl Display for SmartReadError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Io(err) => write!(f, "Input / output error: {err}"),
+			Self::Eof => write!(f, "Reached the end of the input"),
+			Self::Interrupted => write!(f, "Read was interrupted"),
+			Self::Cancelled => write!(f, "Read was cancelled"),
+			Self::Timeout => write!(f, "No input was given before the timeout"),
+			Self::NonInteractive {prompt} => write!(f, "Input is needed for prompt \"{prompt}\", but the terminal is non-interactive and there is no default value"),
+			Self::RetriesExhausted {attempts} => write!(f, "No valid input was given after {attempts} attempts"),
+			Self::InvalidDefault(reason) => write!(f, "Invalid default value: {reason}"),
+			Self::InvalidAnswer {source, answer, reason} => write!(f, "Invalid answer \"{answer}\" from {source}: {reason}"),
+			Self::InvalidAnswerFile(reason) => write!(f, "Invalid answer file: {reason}"),
+			Self::ReplayDiverged {step, expected, found} => write!(f, "Replay diverged from the recording at step {step}: expected {expected}, found {found}"),
+			Self::MissingAnswer {prompt} => write!(f, "The answer file has no answer for prompt \"{prompt}\""),
+			Self::EmptyList => write!(f, "List of options cannot be empty"),
+			Self::CannotHideInput => write!(f, "Cannot read a secret input without showing it, because echo cannot be turned off for this input"),
+			Self::DefaultTerminalInUse => write!(f, "The default terminal is already being used by another read, so a nested read has to use the terminal that it's given"),
+			Self::Other(err) => write!(f, "{err}"),
+		}
+	}
+}
+
+impl From<std::io::Error> for SmartReadError {
+	fn from(err: std::io::Error) -> Self {
+		if matches!(err.get_ref().and_then(|inner| inner.downcast_ref::<Self>()), Some(Self::Cancelled)) {
+			return Self::Cancelled;
+		}
+		match err.kind() {
+			std::io::ErrorKind::Interrupted => Self::Interrupted,
+			std::io::ErrorKind::UnexpectedEof => Self::Eof,
+			std::io::ErrorKind::TimedOut => Self::Timeout,
+			_ => Self::Io(err),
+		}
+	}
+}
+
+impl From<Box<dyn Error + Send + Sync>> for SmartReadError {
+	fn from(err: Box<dyn Error + Send + Sync>) -> Self {
+		Self::Other(err)
+	}
+}
+
+
+
 /// This is what powers the whole crate. Any type that implements this can be used with the macros
 pub trait TryRead {
 	/// Defines the output type of `read` and `prompt` macros
 	type Output;
 	/// Defines the type of the default input
 	type Default;
-	/// This is what's called by the `read` and `prompt` macros
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output>;
+	/// This is what's called by the `read` and `prompt` macros, it runs `try_read_with()` on the default terminal (see `set_default_terminal()`)
+	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> where Self: Sized {
+		with_default_terminal(|term| self.try_read_with(term, prompt, default))?
+	}
+	/// Same as `try_read_line()`, but all input and output goes through the given terminal
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output>;
+	/// Same as `Prompt::try_read()`, this is what the macros use
+	/// 
+	/// Since this is called as a method of the input, auto-referencing still works (for example, a `Vec<InputOption<_>>` is read as a `&[InputOption<_>]`)
+	fn try_read_using<D: IntoDefault<Self::Default>>(self, builder: Prompt<D>) -> ReadResult<Self::Output> where Self: Sized {
+		builder.try_read(self)
+	}
+}
+
+
+
+/// Anything that user input can be read from
+/// 
+/// This is implemented for every type that implements `BufRead`, so you can use things like `&[u8]`, `BufReader<File>`, `BufReader<TcpStream>`, etc
+pub trait InputSource {
+	/// Reads one line of input, without the trailing newline
+	/// 
+	/// This should return `None` once the end of the input is reached (for example, when stdin is closed), so that it isn't mistaken for an empty line, and `std::io::Error::other(SmartReadError::Cancelled)` if the user cancels the read
+	fn next_line(&mut self) -> Result<Option<String>, std::io::Error>;
+	/// Whether a person can answer prompts through this source, this is used to automatically enable non-interactive mode (see `Terminal::is_non_interactive()`)
+	/// 
+	/// By default this returns true
+	fn is_interactive(&self) -> bool {
+		true
+	}
+	/// Same as `next_line()`, but gives up after the given amount of time by returning an error with the kind `TimedOut`
+	/// 
+	/// By default this can't time out, so it just calls `next_line()`
+	fn next_line_timeout(&mut self, timeout: Duration) -> Result<Option<String>, std::io::Error> {
+		let _ = timeout;
+		self.next_line()
+	}
+	/// Turns the echo of typed characters on or off, and returns whether this source supports it, this is used by `SecretInput`
+	/// 
+	/// By default this isn't supported, so it just returns false
+	fn set_echo(&mut self, enabled: bool) -> Result<bool, std::io::Error> {
+		let _ = enabled;
+		Ok(false)
+	}
+	/// Called before a line is typed, with the prompt's id (or the first line of the prompt if it has no id), this is used by `LineEditor` to find earlier answers (see `History`)
+	/// 
+	/// By default this does nothing
+	fn set_prompt_key(&mut self, key: &str) {
+		let _ = key;
+	}
+	/// Called before a line is typed, with the completer to use for the line (see `Terminal::completer`), this is used by `LineEditor` when Tab is pressed
+	/// 
+	/// By default this does nothing
+	fn set_completer(&mut self, completer: Option<Rc<dyn Completer>>) {
+		let _ = completer;
+	}
+}
+
+impl<R: BufRead> InputSource for R {
+	fn next_line(&mut self) -> Result<Option<String>, std::io::Error> {
+		// this works like `read_line()`, except that the line might be a secret, so it's wiped whenever it's moved to a bigger buffer
+		let mut line = SecretBytes::default();
+		loop {
+			let available = match self.fill_buf() {
+				Ok(available) => available,
+				Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+				Err(err) => return Err(err),
+			};
+			if available.is_empty() {break;}
+			let (bytes, is_line_end) = match available.iter().position(|byte| *byte == b'\n') {
+				Some(i) => (&available[..=i], true),
+				None => (available, false),
+			};
+			line.extend(bytes);
+			let len = bytes.len();
+			self.consume(len);
+			if is_line_end {break;}
+		}
+		if line.is_empty() {return Ok(None);}
+		line.strip_suffix(b'\n');
+		line.strip_suffix(b'\r');
+		line.into_string().map(Some)
+	}
+}
+
+/// Reads from the process's stdin, this is the default `InputSource`
+/// 
+/// Reads with a timeout wait for stdin to have input before reading anything, so nothing is left waiting for a line after a timeout, and a line that's typed later is given to whatever reads stdin next. Piped input is read straight away, since it's usually already buffered
+pub struct StdinSource;
+
+impl InputSource for StdinSource {
+	fn next_line(&mut self) -> Result<Option<String>, std::io::Error> {
+		std::io::stdin().lock().next_line()
+	}
+	fn is_interactive(&self) -> bool {
+		std::io::stdin().is_terminal()
+	}
+	fn next_line_timeout(&mut self, timeout: Duration) -> Result<Option<String>, std::io::Error> {
+		// terminals only have input once a whole line is typed, so the line can be read without waiting again
+		if self.is_interactive() && !tty::wait_for_stdin(timeout)? {
+			return Err(std::io::Error::from(std::io::ErrorKind::TimedOut));
+		}
+		self.next_line()
+	}
+	fn set_echo(&mut self, enabled: bool) -> Result<bool, std::io::Error> {
+		tty::set_stdin_echo(enabled)
+	}
+}
+
+
+
+/// Anything that prompts and messages can be written to
+/// 
+/// This is implemented for every type that implements `Write`, so you can use things like `Stdout`, `Vec<u8>`, `TcpStream`, etc
+pub trait OutputSink {
+	/// Writes text for the user to see
+	fn write_text(&mut self, text: &str) -> Result<(), std::io::Error>;
+	/// Makes sure that everything written so far is visible to the user
+	fn flush_output(&mut self) -> Result<(), std::io::Error>;
+}
+
+impl<W: Write> OutputSink for W {
+	fn write_text(&mut self, text: &str) -> Result<(), std::io::Error> {
+		self.write_all(text.as_bytes())
+	}
+	fn flush_output(&mut self) -> Result<(), std::io::Error> {
+		self.flush()
+	}
+}
+
+
+
+/// Holds the input, output, and configuration that a `TryRead` impl should use
+/// 
+/// A terminal can be configured once and then used for a whole session, either directly with methods like `read()` and `prompt()`, or by making it the default terminal that the macros use (see `set_default_terminal()`). Any number of terminals can be used at once, each with their own input and output
+/// 
+/// Example:
+/// 
+/// ```
+/// use smart_read::prelude::*;
+/// use smart_read::{Terminal, TryRead};
+/// 
+/// let mut output = vec!();
+/// let mut term = Terminal::new("42\n".as_bytes(), &mut output);
+/// let input = UsizeInput.try_read_with(&mut term, Some(String::from("Enter a number: ")), None).unwrap();
+/// drop(term);
+/// assert_eq!(input, 42);
+/// assert_eq!(String::from_utf8(output).unwrap(), "Enter a number: ");
+/// ```
+pub struct Terminal<'a> {
+	/// Where user input is read from
+	pub input: Box<dyn InputSource + 'a>,
+	/// Where prompts and messages are written to
+	pub output: Box<dyn OutputSink + 'a>,
+	/// What to do when an invalid input is given, if this is `None` then the global retry policy is used (see `set_default_retry_policy()`)
+	pub retry_policy: Option<RetryPolicy>,
+	/// How long to wait for each input before using the default value (or returning `SmartReadError::Timeout` if there is no default value)
+	pub timeout: Option<Duration>,
+	/// Whether reads should use their default value instead of asking for input, if this is `None` then it's decided automatically (see `is_non_interactive()`)
+	pub non_interactive: Option<bool>,
+	/// A stable name for the current prompt, which lets its answer be given ahead of time
+	/// 
+	/// For example, if this is `"deploy_target"` and the environment variable `SMART_READ_ANSWER_DEPLOY_TARGET` is set, then the variable's value is used as the input (see `preset_answers::env_var_name()`). The answer goes through the same parsing and validation as typed input, and if it is invalid, the read returns `SmartReadError::InvalidAnswer` instead of asking again
+	pub prompt_id: Option<String>,
+	/// Answers to use for prompts with ids, instead of reading input (see `AnswerFile`)
+	pub answer_file: Option<AnswerFile>,
+	/// Records every prompt, input, default value, and accepted suggestion (see `SessionRecorder`)
+	pub recorder: Option<SessionRecorder>,
+	/// Replays a recorded session instead of reading input (see `SessionReplay`)
+	pub replay: Option<SessionReplay>,
+	/// How prompts and messages are styled
+	pub theme: Theme,
+	/// Suggests completions when Tab is pressed, if the input supports it (see `LineEditor`)
+	pub completer: Option<Rc<dyn Completer>>,
+	// the preset answer used by the last `prompt_line()`, if any
+	last_preset_answer: Option<PresetAnswer>,
+}
+
+impl<'a> Terminal<'a> {
+	/// Basic initializer
+	pub fn new(input: impl InputSource + 'a, output: impl OutputSink + 'a) -> Self {
+		Self {
+			input: Box::new(input),
+			output: Box::new(output),
+			retry_policy: None,
+			timeout: None,
+			non_interactive: None,
+			prompt_id: None,
+			answer_file: None,
+			recorder: None,
+			replay: None,
+			theme: Theme::default(),
+			completer: None,
+			last_preset_answer: None,
+		}
+	}
+	/// Initializer for the process's stdin and stdout
+	pub fn stdio() -> Terminal<'static> {
+		Terminal::new(StdinSource, std::io::stdout())
+	}
+	/// Same as `read!()`, but uses this terminal
+	pub fn read<R: TryRead>(&mut self, input: R) -> R::Output {
+		#[allow(clippy::unwrap_used)] // REASON: this is the same as `read!()`, `try_read()` should be used to handle errors
+		self.try_read(input).unwrap()
+	}
+	/// Same as `try_read!()`, but uses this terminal
+	pub fn try_read<R: TryRead>(&mut self, input: R) -> ReadResult<R::Output> {
+		input.try_read_with(self, None, None)
+	}
+	/// Same as `prompt!()`, but uses this terminal
+	pub fn prompt<R: TryRead>(&mut self, prompt: impl Display, input: R) -> R::Output {
+		#[allow(clippy::unwrap_used)] // REASON: this is the same as `prompt!()`, `try_prompt()` should be used to handle errors
+		self.try_prompt(prompt, input).unwrap()
+	}
+	/// Same as `try_prompt!()`, but uses this terminal
+	pub fn try_prompt<R: TryRead>(&mut self, prompt: impl Display, input: R) -> ReadResult<R::Output> {
+		input.try_read_with(self, Some(prompt.to_string()), None)
+	}
+	/// Flushes the output, then reads one line of input
+	/// 
+	/// If the end of the input has been reached, this returns `SmartReadError::Eof`
+	pub fn read_line(&mut self) -> ReadResult<String> {
+		self.last_preset_answer = None;
+		self.output.flush_output()?;
+		match self.input.next_line()? {
+			Some(line) => Ok(line),
+			None => Err(SmartReadError::Eof),
+		}
+	}
+	/// Returns whether reads should use their default value instead of asking for input (or return `SmartReadError::NonInteractive` if there is no default)
+	/// 
+	/// This is decided by the first of these that is set:
+	/// - `self.non_interactive`
+	/// - `set_non_interactive()`
+	/// - the environment variable `SMART_READ_NON_INTERACTIVE` (`1`, `true`, or `yes` to enable, `0`, `false`, or `no` to disable)
+	/// - whether the input is interactive, which for stdin means whether it is a terminal (so piped or closed stdin is non-interactive)
+	/// 
+	/// Secret reads (see `prompt_secret_line()`) only use the first three, since their `NonTtyPolicy` decides what happens when the input isn't a terminal
+	pub fn is_non_interactive(&self) -> bool {
+		self.non_interactive.or_else(default_non_interactive).unwrap_or_else(|| !self.input.is_interactive())
+	}
+	/// Prints the prompt, then reads one line of input, this is what every built-in `TryRead` impl uses
+	/// 
+	/// An empty string means that the default value should be used, so if `has_default` is true then an empty string is also returned when the read times out (see `Terminal::timeout`) or when the terminal is non-interactive (see `is_non_interactive()`)
+	/// 
+	/// If the terminal has a `recorder`, the prompt and input are recorded, and if it has a `replay`, the input comes from the replay instead
+	pub fn prompt_line(&mut self, prompt: &str, has_default: bool) -> ReadResult<String> {
+		self.prompt_line_inner(prompt, has_default, None).map(SecretString::into_string)
+	}
+	/// Same as `prompt_line()`, but typed characters aren't shown, preset answers are shown as `(hidden)`, and only `SessionEvent::SecretInput` is recorded
+	/// 
+	/// If the input can't be hidden (for example, when stdin isn't a terminal), then `non_tty_policy` decides whether the line is read anyway or `SmartReadError::CannotHideInput` is returned. This is decided before the input's own non-interactive detection, so piped secrets are read, but non-interactive mode that's set explicitly (see `is_non_interactive()`) still applies. The terminal's echo is turned back on when the read finishes, even if it panics or Ctrl-C is pressed
+	/// 
+	/// Since secrets aren't recorded, they're read from the input as usual while replaying a session
+	pub fn prompt_secret_line(&mut self, prompt: &str, non_tty_policy: NonTtyPolicy) -> ReadResult<SecretString> {
+		self.prompt_line_inner(prompt, false, Some(non_tty_policy))
+	}
+	// every line is kept in a `SecretString` from the moment it's read, so that a secret is still wiped if a later step fails
+	fn prompt_line_inner(&mut self, prompt: &str, has_default: bool, secret: Option<NonTtyPolicy>) -> ReadResult<SecretString> {
+		self.last_preset_answer = None;
+		self.log_event(SessionEvent::Prompt(prompt.to_string()))?;
+		let input = match &self.replay {
+			Some(replay) if secret.is_none() => {
+				let input = replay.peek_answer()?;
+				self.print_prompt(prompt)?;
+				self.println(&input)?;
+				SecretString::from(input)
+			}
+			_ => self.read_prompt_line(prompt, has_default, secret)?,
+		};
+		if secret.is_some() {
+			self.log_event(SessionEvent::SecretInput)?;
+		} else if input.is_empty() && has_default {
+			self.log_event(SessionEvent::DefaultUsed)?;
+		} else {
+			self.log_event(SessionEvent::Input(input.expose_secret().to_string()))?;
+		}
+		Ok(input)
+	}
+	/// Gives an event to the terminal's `recorder` and checks it against the terminal's `replay`
+	pub fn log_event(&mut self, event: SessionEvent) -> ReadResult<()> {
+		if let Some(replay) = &self.replay {
+			replay.check(&event)?;
+		}
+		if let Some(recorder) = &self.recorder {
+			recorder.record(event);
+		}
+		Ok(())
+	}
+	fn read_prompt_line(&mut self, prompt: &str, has_default: bool, secret: Option<NonTtyPolicy>) -> ReadResult<SecretString> {
+		let preset_answer = self.find_preset_answer(prompt)?;
+		self.print_prompt(prompt)?;
+		if let Some(PresetAnswer {source, answer}) = preset_answer {
+			let answer = SecretString::from(answer);
+			// secrets are only kept in the returned string, so they can't show up in later errors
+			let shown_answer = if secret.is_some() {String::from("(hidden)")} else {answer.expose_secret().to_string()};
+			self.println(&format!("{shown_answer} (from {source})"))?;
+			self.last_preset_answer = Some(PresetAnswer {source, answer: shown_answer});
+			return Ok(answer);
+		}
+		let first_line = prompt.trim().lines().next().unwrap_or_default().trim();
+		// for secrets, an input that isn't a terminal is handled by the `NonTtyPolicy` below instead
+		let non_interactive = match secret {
+			Some(_) => self.non_interactive.or_else(default_non_interactive).unwrap_or(false),
+			None => self.is_non_interactive(),
+		};
+		if non_interactive {
+			self.println("")?;
+			if !has_default {
+				return Err(SmartReadError::NonInteractive {prompt: first_line.to_string()});
+			}
+			self.print_note("Non-interactive, using the default value")?;
+			return Ok(SecretString::default());
+		}
+		self.input.set_prompt_key(self.prompt_id.as_deref().unwrap_or(first_line));
+		self.input.set_completer(self.completer.clone());
+		let Some(non_tty_policy) = secret else {
+			return self.read_typed_line(has_default, false);
+		};
+		let mut term = EchoGuard {echo_disabled: self.input.set_echo(false)?, term: self};
+		if !term.echo_disabled && non_tty_policy == NonTtyPolicy::Refuse {
+			term.println("")?;
+			return Err(SmartReadError::CannotHideInput);
+		}
+		let echo_disabled = term.echo_disabled;
+		term.read_typed_line(has_default, echo_disabled)
+	}
+	fn read_typed_line(&mut self, has_default: bool, echo_disabled: bool) -> ReadResult<SecretString> {
+		let Some(timeout) = self.timeout else {
+			let line = SecretString::from(self.read_line()?);
+			if echo_disabled {self.println("")?;} // the typed newline isn't shown
+			return Ok(line);
+		};
+		self.print(&self.theme.paint_note(&format!("(times out in {timeout:?})")))?;
+		self.print(" ")?;
+		self.output.flush_output()?;
+		match self.input.next_line_timeout(timeout) {
+			Ok(Some(line)) => {
+				let line = SecretString::from(line);
+				if echo_disabled {self.println("")?;} // the typed newline isn't shown
+				Ok(line)
+			}
+			Ok(None) => Err(SmartReadError::Eof),
+			Err(err) if err.kind() == std::io::ErrorKind::TimedOut => {
+				self.println("")?;
+				if !has_default {
+					self.print_note("Timed out")?;
+					return Err(SmartReadError::Timeout);
+				}
+				self.print_note("Timed out, using the default value")?;
+				Ok(SecretString::default())
+			}
+			Err(err) => Err(err.into()),
+		}
+	}
+	fn find_preset_answer(&self, prompt: &str) -> ReadResult<Option<PresetAnswer>> {
+		if let Some(prompt_id) = self.prompt_id.as_deref() && let Some(answer) = preset_answers::env_answer(prompt_id) {
+			if let Some(answer_file) = &self.answer_file {
+				answer_file.mark_overridden(prompt_id);
+			}
+			return Ok(Some(answer));
+		}
+		let Some(answer_file) = &self.answer_file else {return Ok(None);};
+		if let Some(prompt_id) = self.prompt_id.as_deref() && let Some(answer) = answer_file.answer(prompt_id) {
+			return Ok(Some(answer));
+		}
+		if answer_file.allows_fallback() {return Ok(None);}
+		let prompt = self.prompt_id.clone().unwrap_or_else(|| prompt.trim().lines().next().unwrap_or_default().trim().to_string());
+		Err(SmartReadError::MissingAnswer {prompt})
+	}
+	/// Returns the preset answer that was used by the last `prompt_line()`, if any (see `prompt_id`)
+	/// 
+	/// This is cleared at the start of every read, including `read_line()` and replayed reads
+	pub fn last_preset_answer(&self) -> Option<&PresetAnswer> {
+		self.last_preset_answer.as_ref()
+	}
+	/// Writes text to the output
+	pub fn print(&mut self, text: &str) -> Result<(), std::io::Error> {
+		self.output.write_text(text)
+	}
+	/// Writes text and a newline to the output
+	pub fn println(&mut self, text: &str) -> Result<(), std::io::Error> {
+		self.output.write_text(text)?;
+		self.output.write_text("\n")
+	}
+	/// Writes a prompt to the output, styled with `theme.prompt`
+	pub fn print_prompt(&mut self, prompt: &str) -> Result<(), std::io::Error> {
+		let prompt = self.theme.paint_prompt(prompt);
+		self.print(&prompt)
+	}
+	/// Writes a message about an invalid input and a newline to the output, styled with `theme.error`
+	pub fn print_error(&mut self, message: &str) -> Result<(), std::io::Error> {
+		let message = self.theme.paint_error(message);
+		self.println(&message)
+	}
+	/// Writes a message from the crate (such as "Timed out, using the default value") and a newline to the output, styled with `theme.note`
+	pub fn print_note(&mut self, message: &str) -> Result<(), std::io::Error> {
+		let message = self.theme.paint_note(message);
+		self.println(&message)
+	}
+}
+
+/// Another name for `Terminal`, for when it's used as a session that holds the I/O and configuration for many reads
+/// 
+/// ```
+/// use smart_read::prelude::*;
+/// 
+/// let (mut output_a, mut output_b) = (vec!(), vec!());
+/// let mut prompter_a = Prompter::new("12\n".as_bytes(), &mut output_a);
+/// let mut prompter_b = Prompter::new("yes\n".as_bytes(), &mut output_b);
+/// prompter_a.theme = Theme::colored();
+/// prompter_b.retry_policy = Some(RetryPolicy::max_attempts(3));
+/// 
+/// assert_eq!(prompter_a.prompt("Enter a number: ", UsizeInput), 12);
+/// assert!(prompter_b.read(YesNoInput));
+/// ```
+pub type Prompter<'a> = Terminal<'a>;
+
+
+
+// turns echo back on when it's dropped, so that the terminal is restored even if the read panics
+struct EchoGuard<'t, 'a> {
+	term: &'t mut Terminal<'a>,
+	echo_disabled: bool,
+}
+
+impl<'a> std::ops::Deref for EchoGuard<'_, 'a> {
+	type Target = Terminal<'a>;
+	fn deref(&self) -> &Self::Target {
+		self.term
+	}
+}
+
+impl std::ops::DerefMut for EchoGuard<'_, '_> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		self.term
+	}
+}
+
+impl Drop for EchoGuard<'_, '_> {
+	fn drop(&mut self) {
+		if self.echo_disabled {
+			let _ = self.term.input.set_echo(true);
+		}
+	}
+}
+
+
+
+/// Styles for the text that a `Terminal` prints, as ANSI escape codes
+/// 
+/// The default theme has no styling
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Theme {
+	/// Used for prompts
+	pub prompt: String,
+	/// Used for messages about invalid inputs
+	pub error: String,
+	/// Used for messages from the crate itself, such as "Timed out, using the default value"
+	pub note: String,
+}
+
+impl Theme {
+	/// Bold prompts, red error messages, and dim notes
+	pub fn colored() -> Self {
+		Self {
+			prompt: String::from("\x1b[1m"),
+			error: String::from("\x1b[31m"),
+			note: String::from("\x1b[2m"),
+		}
+	}
+	/// Styles text with `self.prompt`
+	pub fn paint_prompt(&self, text: &str) -> String {
+		paint(&self.prompt, text)
+	}
+	/// Styles text with `self.error`
+	pub fn paint_error(&self, text: &str) -> String {
+		paint(&self.error, text)
+	}
+	/// Styles text with `self.note`
+	pub fn paint_note(&self, text: &str) -> String {
+		paint(&self.note, text)
+	}
+}
+
+fn paint(style: &str, text: &str) -> String {
+	if style.is_empty() || text.is_empty() {return text.to_string();}
+	format!("{style}{text}\x1b[0m")
+}
+
+
+
+
+
+/// Controls what happens when an invalid input is given
+/// 
+/// By default, inputs are asked for again and again until a valid one is given
+/// 
+/// Example:
+/// 
+/// ```ignore
+/// // give up after 3 tries, waiting 1 second after the first invalid input, then 2 seconds, then 4 seconds, etc (up to `max_delay`):
+/// let pin = prompt!("Enter PIN: "; retry_policy = RetryPolicy::max_attempts(3).with_delay(Duration::from_secs(1)).with_backoff(2.); U32Input);
+/// 
+/// // same, but use the default value instead of returning an error:
+/// let confirm = prompt!("Continue? "; retry_policy = RetryPolicy::max_attempts(3).or_use_default(); [false] YesNoInput);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+	/// The maximum number of inputs to try, or `None` for no limit
+	pub max_attempts: Option<usize>,
+	/// How long to wait after the first invalid input
+	pub delay: Duration,
+	/// How much the delay is multiplied by after each invalid input (`1.0` keeps the delay the same)
+	pub backoff: f32,
+	/// The longest the delay can grow to, this is 60 seconds by default
+	pub max_delay: Duration,
+	/// What to do once `max_attempts` invalid inputs have been given
+	pub on_exhausted: OnRetriesExhausted,
+}
+
+/// What to do once a `RetryPolicy`'s max attempts have been used up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnRetriesExhausted {
+	/// Return `SmartReadError::RetriesExhausted`
+	ReturnError,
+	/// Return the default value, or `SmartReadError::RetriesExhausted` if there is no default value
+	UseDefault,
+}
+
+impl RetryPolicy {
+	/// Asks forever without waiting, this is the default policy
+	pub const UNLIMITED: Self = Self {
+		max_attempts: None,
+		delay: Duration::ZERO,
+		backoff: 1.,
+		max_delay: Duration::from_secs(60),
+		on_exhausted: OnRetriesExhausted::ReturnError,
+	};
+	/// Initializer for a policy that gives up after the given number of invalid inputs
+	pub fn max_attempts(max_attempts: usize) -> Self {
+		Self {
+			max_attempts: Some(max_attempts),
+			..Self::UNLIMITED
+		}
+	}
+	/// Sets how long to wait after the first invalid input
+	pub fn with_delay(mut self, delay: Duration) -> Self {
+		self.delay = delay;
+		self
+	}
+	/// Sets how much the delay is multiplied by after each invalid input
+	pub fn with_backoff(mut self, backoff: f32) -> Self {
+		self.backoff = backoff;
+		self
+	}
+	/// Sets the longest the delay can grow to
+	pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+		self.max_delay = max_delay;
+		self
+	}
+	/// Makes the read return the default value (if there is one) instead of an error once the max attempts are used up
+	pub fn or_use_default(mut self) -> Self {
+		self.on_exhausted = OnRetriesExhausted::UseDefault;
+		self
+	}
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self::UNLIMITED
+	}
+}
+
+
+
+/// Keeps track of the invalid inputs given during a single read, and applies the `RetryPolicy`
+/// 
+/// Every built-in `TryRead` impl uses this (mostly through `run_prompt_loop()`), and custom impls that have their own loop should too:
+/// 
+/// ```ignore
+/// let mut retries = RetryCounter::new(term);
+/// loop {
+/// 	let input = term.read_line()?;
+/// 	if is_valid(&input) {return Ok(input);}
+/// 	if let Some(default) = retries.invalid_input(term, "Invalid input", &mut default)? {
+/// 		return Ok(default);
+/// 	}
+/// }
+/// ```
+pub struct RetryCounter {
+	policy: RetryPolicy,
+	attempts: usize,
+	next_delay: Duration,
+}
+
+impl RetryCounter {
+	/// Starts counting with the terminal's retry policy
+	pub fn new(term: &Terminal) -> Self {
+		let policy = term.retry_policy.clone().unwrap_or_else(default_retry_policy);
+		Self {
+			next_delay: policy.delay,
+			policy,
+			attempts: 0,
+		}
+	}
+	/// Call this when an invalid input is given, it prints the given message and waits for the policy's delay
+	/// 
+	/// Returns `Ok(None)` if the input should be asked for again, `Ok(Some(default))` if the default should be used instead, or `Err(SmartReadError::RetriesExhausted)` if the read should give up
+	/// 
+	/// If the invalid input was a preset answer (see `Terminal::prompt_id`), this always returns `Err(SmartReadError::InvalidAnswer)`
+	pub fn invalid_input<T>(&mut self, term: &mut Terminal, message: &str, default: &mut Option<T>) -> ReadResult<Option<T>> {
+		if let Some(PresetAnswer {source, answer}) = term.last_preset_answer() {
+			return Err(SmartReadError::InvalidAnswer {source: source.clone(), answer: answer.clone(), reason: message.to_string()});
+		}
+		self.attempts += 1;
+		term.println("")?;
+		let Some(max_attempts) = self.policy.max_attempts else {
+			term.print_error(message)?;
+			self.wait();
+			return Ok(None);
+		};
+		if self.attempts < max_attempts {
+			let attempts_left = max_attempts - self.attempts;
+			term.print_error(&format!("{message} ({attempts_left} {} left)", if attempts_left == 1 {"attempt"} else {"attempts"}))?;
+			self.wait();
+			return Ok(None);
+		}
+		term.print_error(message)?;
+		match (self.policy.on_exhausted, default.take()) {
+			(OnRetriesExhausted::UseDefault, Some(default)) => Ok(Some(default)),
+			(_, _) => Err(SmartReadError::RetriesExhausted {attempts: self.attempts}),
+		}
+	}
+	fn wait(&mut self) {
+		if self.next_delay.is_zero() {return;}
+		std::thread::sleep(self.next_delay.min(self.policy.max_delay));
+		self.increase_delay();
+	}
+	// a huge or infinite backoff stops at `max_delay` instead of overflowing, and a negative or NaN backoff stops the delays
+	fn increase_delay(&mut self) {
+		let next_delay = self.next_delay.as_secs_f64() * f64::from(self.policy.backoff.max(0.));
+		self.next_delay = Duration::try_from_secs_f64(next_delay).unwrap_or(Duration::MAX).min(self.policy.max_delay);
+	}
+}
+
+
+
+/// The parts of an input type that `run_prompt_loop()` can't do on its own
+/// 
+/// Every built-in input type (other than lists) is built on this, so implementing it is the easiest way to make a custom input type that handles prompts, defaults, retries, and preset answers the same way. If the output implements `Display`, implementing `DisplayPromptHooks` instead also shows default values with it:
+/// 
+/// ```
+/// use smart_read::*;
+/// 
+/// struct EvenInput;
+/// 
+/// impl DisplayPromptHooks for EvenInput {
+/// 	type Output = u32;
+/// 	fn default_prompt(&self) -> String {
+/// 		String::from("Enter an even number: ")
+/// 	}
+/// 	fn parse(&self, input: String) -> Result<u32, String> {
+/// 		input.parse().map_err(|err| format!("Could not parse input (error: {err})"))
+/// 	}
+/// 	fn validate(&self, output: &u32) -> Result<(), String> {
+/// 		if output % 2 == 0 {Ok(())} else {Err(String::from("Invalid input, must be even"))}
+/// 	}
+/// }
+/// 
+/// impl TryRead for EvenInput {
+/// 	type Output = u32;
+/// 	type Default = u32;
+/// 	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<u32>) -> ReadResult<u32> {
+/// 		run_prompt_loop(term, &self, prompt, default)
+/// 	}
+/// }
+/// ```
+pub trait PromptHooks {
+	/// The type that inputs are parsed into
+	type Output;
+	/// The prompt to use when none is given
+	fn default_prompt(&self) -> String {
+		String::new()
+	}
+	/// Formats the default value, which is shown as `(default: ...)` after the prompt
+	/// 
+	/// This is done with `Display` for every `DisplayPromptHooks`, so it only has to be written for outputs that don't implement `Display` (or that should be shown differently)
+	fn render_default(&self, default: &Self::Output) -> String;
+	/// Converts an input into the output type, or returns the message to show for an invalid input
+	fn parse(&self, input: String) -> Result<Self::Output, String>;
+	/// Checks a parsed input, or returns the message to show for an invalid input
+	fn validate(&self, _output: &Self::Output) -> Result<(), String> {
+		Ok(())
+	}
+	/// Runs `parse()` and then `validate()`
+	fn parse_and_validate(&self, input: String) -> Result<Self::Output, String> {
+		let output = self.parse(input)?;
+		self.validate(&output)?;
+		Ok(output)
+	}
+}
+
+/// Same as `PromptHooks`, but for outputs that implement `Display`, which is used to show default values
+/// 
+/// Every type that implements this also implements `PromptHooks`
+pub trait DisplayPromptHooks {
+	/// The type that inputs are parsed into
+	type Output: Display;
+	/// The prompt to use when none is given
+	fn default_prompt(&self) -> String {
+		String::new()
+	}
+	/// Converts an input into the output type, or returns the message to show for an invalid input
+	fn parse(&self, input: String) -> Result<Self::Output, String>;
+	/// Checks a parsed input, or returns the message to show for an invalid input
+	fn validate(&self, _output: &Self::Output) -> Result<(), String> {
+		Ok(())
+	}
+}
+
+impl<H: DisplayPromptHooks> PromptHooks for H {
+	type Output = H::Output;
+	fn default_prompt(&self) -> String {
+		DisplayPromptHooks::default_prompt(self)
+	}
+	fn render_default(&self, default: &Self::Output) -> String {
+		default.to_string()
+	}
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		DisplayPromptHooks::parse(self, input)
+	}
+	fn validate(&self, output: &Self::Output) -> Result<(), String> {
+		DisplayPromptHooks::validate(self, output)
+	}
 }
 
 
@@ -288,14 +1312,120 @@ pub trait TryRead {
 
 
 
-/// Utility function, mostly for internal use
-pub fn read_stdin() -> Result<String, std::io::Error> {
-	std::io::stdout().flush()?;
-	let mut output = String::new();
-	std::io::stdin().read_line(&mut output)?;
-	if output.ends_with('\n') {output.pop();}
-	if output.ends_with('\r') {output.pop();}
-	Ok(output)
+/// Keeps prompting for input until it's successfully parsed and validated by `hooks`
+/// 
+/// An empty input returns the default value (if there is one), and every invalid input goes through the terminal's retry policy (see `RetryCounter`)
+pub fn run_prompt_loop<H: PromptHooks>(term: &mut Terminal, hooks: &H, prompt: Option<String>, default: Option<H::Output>) -> ReadResult<H::Output> {
+	let mut prompt = prompt.unwrap_or_else(|| hooks.default_prompt());
+	if let Some(default) = default.as_ref() {
+		prompt += &format!("(default: {}) ", hooks.render_default(default));
+	}
+	let mut retries = RetryCounter::new(term);
+	let mut default = default;
+	loop {
+		
+		let input = term.prompt_line(&prompt, default.is_some())?;
+		if input.is_empty() && let Some(default) = default {
+			return Ok(default);
+		}
+		
+		let error_message = match hooks.parse_and_validate(input) {
+			Ok(output) => return Ok(output),
+			Err(error_message) => error_message,
+		};
+		if let Some(default) = retries.invalid_input(term, &error_message, &mut default)? {
+			return Ok(default);
+		}
+		
+	}
+}
+
+/// Utility function, adds the input to an error message with a `^` under the given byte position, for showing where an invalid input went wrong
+pub fn error_at(input: &str, position: usize, message: &str) -> String {
+	let column = input[..position].chars().count();
+	format!("{message}\n{input}\n{}^", " ".repeat(column))
+}
+
+
+
+/// Utility function, reads one line from stdin
+/// 
+/// If stdin has been closed, this returns `SmartReadError::Eof` (an empty line is returned as an empty string)
+pub fn read_stdin() -> ReadResult<String> {
+	Terminal::stdio().read_line()
+}
+
+
+
+thread_local! {
+	static DEFAULT_TERMINAL: RefCell<Option<Terminal<'static>>> = const { RefCell::new(None) };
+	static IS_DEFAULT_TERMINAL_IN_USE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets the terminal used by `read!()`, `prompt!()`, `try_read_line()`, etc on the current thread, and returns the previous one
+/// 
+/// Until this is called, the default terminal is `Terminal::stdio()`
+pub fn set_default_terminal(term: Terminal<'static>) -> Terminal<'static> {
+	DEFAULT_TERMINAL.with(|default_term| default_term.borrow_mut().replace(term)).unwrap_or_else(Terminal::stdio)
+}
+
+/// Runs the given function with the current thread's default terminal
+/// 
+/// If this is called from within another `with_default_terminal()` (for example, a `TryRead` impl that uses `read!()` internally), the inner call returns `SmartReadError::DefaultTerminalInUse` instead of running the function. The terminal is put back afterwards, even if the function panics
+pub fn with_default_terminal<T>(f: impl FnOnce(&mut Terminal) -> T) -> ReadResult<T> {
+	if IS_DEFAULT_TERMINAL_IN_USE.with(|is_in_use| is_in_use.replace(true)) {
+		return Err(SmartReadError::DefaultTerminalInUse);
+	}
+	let term = DEFAULT_TERMINAL.with(|default_term| default_term.borrow_mut().take()).unwrap_or_else(Terminal::stdio);
+	let mut guard = DefaultTerminalGuard(Some(term));
+	#[allow(clippy::unwrap_used)] // REASON: the guard only gives up the terminal when it's dropped
+	Ok(f(guard.0.as_mut().unwrap()))
+}
+
+// puts the default terminal back when it's dropped, so that it isn't lost if a read panics
+struct DefaultTerminalGuard(Option<Terminal<'static>>);
+
+impl Drop for DefaultTerminalGuard {
+	fn drop(&mut self) {
+		DEFAULT_TERMINAL.with(|default_term| *default_term.borrow_mut() = self.0.take());
+		IS_DEFAULT_TERMINAL_IN_USE.with(|is_in_use| is_in_use.set(false));
+	}
+}
+
+
+
+static DEFAULT_RETRY_POLICY: Mutex<RetryPolicy> = Mutex::new(RetryPolicy::UNLIMITED);
+
+/// Sets the retry policy used by every read that doesn't have its own (see `Terminal::retry_policy`), on all threads
+pub fn set_default_retry_policy(policy: RetryPolicy) {
+	*DEFAULT_RETRY_POLICY.lock().unwrap_or_else(PoisonError::into_inner) = policy;
+}
+
+/// Gets the retry policy used by every read that doesn't have its own
+pub fn default_retry_policy() -> RetryPolicy {
+	DEFAULT_RETRY_POLICY.lock().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+
+
+static NON_INTERACTIVE: Mutex<Option<bool>> = Mutex::new(None);
+
+/// Forces non-interactive mode on or off for every terminal that doesn't have its own setting, on all threads, or `None` to decide automatically (see `Terminal::is_non_interactive()`)
+pub fn set_non_interactive(non_interactive: Option<bool>) {
+	*NON_INTERACTIVE.lock().unwrap_or_else(PoisonError::into_inner) = non_interactive;
+}
+
+/// Gets the value set by `set_non_interactive()`, or if that isn't set, the value of the environment variable `SMART_READ_NON_INTERACTIVE`
+pub fn default_non_interactive() -> Option<bool> {
+	if let Some(non_interactive) = *NON_INTERACTIVE.lock().unwrap_or_else(PoisonError::into_inner) {
+		return Some(non_interactive);
+	}
+	let env_value = std::env::var("SMART_READ_NON_INTERACTIVE").ok()?;
+	match &*env_value.trim().to_lowercase() {
+		"1" | "true" | "yes" => Some(true),
+		"0" | "false" | "no" => Some(false),
+		_ => None,
+	}
 }
 
 
@@ -312,6 +1442,359 @@ pub fn clear_term() {
 /// This is basically a wrapper for `prompt!("Press enter to continue ")`
 pub fn wait_for_enter() {
 	// this would be `prompt!("Press...")`, but that causes an error because of scopes
-	print!("Press enter to continue ");
-	let _ = read_stdin();
+	let _ = with_default_terminal(|term| {
+		let _ = term.print("Press enter to continue ");
+		let _ = term.read_line();
+	});
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use basics::UsizeInput;
+	use testing::ScriptedTerminal;
+	
+	// a custom input that (wrongly) uses the default terminal instead of the one it's given
+	struct NestedInput;
+	
+	impl TryRead for NestedInput {
+		type Output = String;
+		type Default = ();
+		fn try_read_with(self, _term: &mut Terminal, _prompt: Option<String>, _default: Option<Self::Default>) -> ReadResult<Self::Output> {
+			().try_read_line(None, None)
+		}
+	}
+	
+	#[test]
+	fn nested_reads_do_not_use_stdin() {
+		let script = ScriptedTerminal::new(&["answer"]);
+		let output = script.run(|| NestedInput.try_read_line(None, None));
+		assert!(matches!(output, Err(SmartReadError::DefaultTerminalInUse)), "{output:?}");
+		assert_eq!(script.remaining_answers(), 1);
+	}
+	
+	#[test]
+	fn default_terminal_is_kept_after_a_panic() {
+		let script = ScriptedTerminal::new(&["answer"]);
+		let prev_term = set_default_terminal(script.terminal());
+		#[allow(clippy::panic)] // REASON: this tests what happens when a read panics
+		let result = std::panic::catch_unwind(|| with_default_terminal(|_| panic!("read failed")));
+		assert!(result.is_err());
+		let output = ().try_read_line(None, None);
+		set_default_terminal(prev_term);
+		assert_eq!(output.ok().as_deref(), Some("answer"));
+	}
+	
+	#[test]
+	fn retries_run_out() {
+		let script = ScriptedTerminal::new(&["a", "b", "5"]);
+		let output = Prompt::new("n: ").retries(2).try_read_with(&mut script.terminal(), UsizeInput);
+		assert!(matches!(output, Err(SmartReadError::RetriesExhausted {attempts: 2})), "{output:?}");
+		script.assert_shown("(1 attempt left)");
+		assert_eq!(script.remaining_answers(), 1);
+	}
+	
+	#[test]
+	fn default_is_used_once_retries_run_out() {
+		let script = ScriptedTerminal::new(&["a", "b"]);
+		let output = Prompt::new("n: ").retry_policy(RetryPolicy::max_attempts(2).or_use_default()).default(7_usize).try_read_with(&mut script.terminal(), UsizeInput);
+		assert_eq!(output.ok(), Some(7));
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn use_default_without_a_default_is_an_error() {
+		let script = ScriptedTerminal::new(&["a"]);
+		let output = Prompt::new("n: ").retry_policy(RetryPolicy::max_attempts(1).or_use_default()).try_read_with(&mut script.terminal(), UsizeInput);
+		assert!(matches!(output, Err(SmartReadError::RetriesExhausted {attempts: 1})), "{output:?}");
+	}
+	
+	#[test]
+	fn valid_input_within_the_limit_is_used() {
+		let script = ScriptedTerminal::new(&["a", "5"]);
+		let output = Prompt::new("n: ").retries(2).try_read_with(&mut script.terminal(), UsizeInput);
+		assert_eq!(output.ok(), Some(5));
+	}
+	
+	// a custom input that follows the documented `read_line()` + `RetryCounter` pattern
+	struct EvenInput;
+	
+	impl TryRead for EvenInput {
+		type Output = usize;
+		type Default = usize;
+		fn try_read_with(self, term: &mut Terminal, _prompt: Option<String>, mut default: Option<usize>) -> ReadResult<usize> {
+			let mut retries = RetryCounter::new(term);
+			loop {
+				let input = term.read_line()?;
+				if let Ok(output) = input.parse::<usize>() && output % 2 == 0 {return Ok(output);}
+				if let Some(default) = retries.invalid_input(term, "Invalid input, must be even", &mut default)? {
+					return Ok(default);
+				}
+			}
+		}
+	}
+	
+	// each test uses its own variables, since the tests run in parallel
+	fn set_env_answer(prompt_id: &str, answer: &str) {
+		// SAFETY: no other test reads or writes this variable
+		unsafe {std::env::set_var(preset_answers::env_var_name(prompt_id), answer);}
+	}
+	
+	#[test]
+	fn env_answers_are_used() {
+		set_env_answer("test_env_port", "8080");
+		let script = ScriptedTerminal::new(&["1"]);
+		let mut term = script.terminal();
+		let output = Prompt::new("Port: ").id("test_env_port").try_read_with(&mut term, UsizeInput);
+		assert_eq!(output.ok(), Some(8080));
+		assert!(term.last_preset_answer().is_some_and(|preset| preset.answer == "8080"));
+		let output = Prompt::new("Count: ").try_read_with(&mut term, UsizeInput);
+		assert_eq!(output.ok(), Some(1));
+		assert!(term.last_preset_answer().is_none());
+		script.assert_shown("8080 (from SMART_READ_ANSWER_TEST_ENV_PORT)");
+	}
+	
+	#[test]
+	fn invalid_env_answers_are_errors() {
+		set_env_answer("test_env_count", "many");
+		let script = ScriptedTerminal::new(&["1"]);
+		let output = Prompt::new("Count: ").id("test_env_count").try_read_with(&mut script.terminal(), UsizeInput);
+		assert!(matches!(&output, Err(SmartReadError::InvalidAnswer {source, answer, ..}) if source == "SMART_READ_ANSWER_TEST_ENV_COUNT" && answer == "many"), "{output:?}");
+		assert_eq!(script.remaining_answers(), 1);
+	}
+	
+	#[test]
+	fn env_answers_do_not_affect_later_reads() {
+		set_env_answer("test_env_host", "example.com");
+		let script = ScriptedTerminal::new(&["3", "4", "x", "6"]);
+		let mut term = script.terminal();
+		let output = Prompt::new("Host: ").id("test_env_host").try_read_with(&mut term, ());
+		assert_eq!(output.ok().as_deref(), Some("example.com"));
+		assert_eq!(EvenInput.try_read_with(&mut term, None, None).ok(), Some(4));
+		
+		// replayed reads don't use preset answers either
+		let output = Prompt::new("Host: ").id("test_env_host").try_read_with(&mut term, ());
+		assert_eq!(output.ok().as_deref(), Some("example.com"));
+		term.replay = Some(SessionReplay::new(vec!(SessionEvent::Prompt(String::from("Count: ")), SessionEvent::Input(String::from("x")))));
+		let output = Prompt::new("Count: ").retries(2).try_read_with(&mut term, UsizeInput);
+		assert!(!matches!(output, Err(SmartReadError::InvalidAnswer {..})), "{output:?}");
+		term.replay = None;
+		assert_eq!(EvenInput.try_read_with(&mut term, None, None).ok(), Some(6));
+	}
+	
+	#[test]
+	fn timeouts_use_the_default() {
+		let script = ScriptedTerminal::new::<&str>(&[]);
+		script.push_timeout();
+		script.push_answer("5");
+		let mut term = script.terminal();
+		let output = Prompt::new("n: ").timeout(Duration::from_secs(30)).default(3_usize).try_read_with(&mut term, UsizeInput);
+		assert_eq!(output.ok(), Some(3));
+		script.assert_shown("(times out in 30s)");
+		script.assert_shown("Timed out, using the default value");
+		
+		// the answer after a timeout goes to the next read
+		let output = Prompt::new("n: ").timeout(Duration::from_secs(30)).default(3_usize).try_read_with(&mut term, UsizeInput);
+		assert_eq!(output.ok(), Some(5));
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn timeouts_without_a_default_are_errors() {
+		let script = ScriptedTerminal::new::<&str>(&[]);
+		script.push_timeout();
+		script.push_answer("y");
+		let mut term = script.terminal();
+		let output = Prompt::new("Continue? ").timeout(Duration::from_secs(30)).try_read_with(&mut term, basics::YesNoInput);
+		assert!(matches!(output, Err(SmartReadError::Timeout)), "{output:?}");
+		script.assert_shown("Timed out");
+		assert_eq!(Prompt::new("Continue? ").try_read_with(&mut term, basics::YesNoInput).ok(), Some(true));
+	}
+	
+	#[test]
+	fn list_reads_time_out() {
+		let script = ScriptedTerminal::new::<&str>(&[]);
+		script.push_timeout();
+		let output = Prompt::new("Pick one: ").timeout(Duration::from_secs(30)).default(1_usize).try_read_with(&mut script.terminal(), ["red", "green", "blue"]);
+		assert_eq!(output.ok(), Some((1, "green")));
+		script.assert_shown("Timed out, using the default value");
+	}
+	
+	// an input where the user cancels every read
+	struct CancelledInput;
+	
+	impl InputSource for CancelledInput {
+		fn next_line(&mut self) -> Result<Option<String>, std::io::Error> {
+			Err(std::io::Error::other(SmartReadError::Cancelled))
+		}
+	}
+	
+	#[test]
+	fn cancelled_reads_are_errors() {
+		let mut term = Terminal::new(CancelledInput, std::io::sink());
+		let output = Prompt::new("n: ").default(3_usize).try_read_with(&mut term, UsizeInput);
+		assert!(matches!(output, Err(SmartReadError::Cancelled)), "{output:?}");
+		let output = SmartReadError::from(std::io::Error::other(SmartReadError::Eof));
+		assert!(matches!(output, SmartReadError::Io(_)), "{output:?}");
+	}
+	
+	fn delays(policy: RetryPolicy, count: usize) -> Vec<Duration> {
+		let mut term = Terminal::new(std::io::empty(), std::io::sink());
+		term.retry_policy = Some(policy);
+		let mut retries = RetryCounter::new(&term);
+		(0..count).map(|_| {retries.increase_delay(); retries.next_delay}).collect()
+	}
+	
+	#[test]
+	fn delays_back_off_up_to_the_max() {
+		let policy = RetryPolicy::max_attempts(10).with_delay(Duration::from_secs(1)).with_backoff(2.).with_max_delay(Duration::from_secs(5));
+		assert_eq!(delays(policy, 4), [2, 4, 5, 5].map(Duration::from_secs));
+		let policy = RetryPolicy::UNLIMITED.with_delay(Duration::from_secs(1000)).with_backoff(f32::MAX);
+		assert_eq!(delays(policy, 3), [Duration::from_secs(60); 3]);
+		let policy = RetryPolicy::UNLIMITED.with_delay(Duration::from_secs(1)).with_backoff(f32::INFINITY).with_max_delay(Duration::MAX);
+		assert_eq!(delays(policy, 2), [Duration::MAX; 2]);
+		for backoff in [f32::NAN, -1.] {
+			let policy = RetryPolicy::UNLIMITED.with_delay(Duration::from_secs(1)).with_backoff(backoff);
+			assert_eq!(delays(policy, 2), [Duration::ZERO; 2]);
+		}
+	}
+	
+	#[test]
+	fn defaults_are_shown_with_display() {
+		let script = ScriptedTerminal::new(&["", ""]);
+		let mut term = script.terminal();
+		assert_eq!(Prompt::new("n ").default(5).try_read_with(&mut term, 1..=10).ok(), Some(5));
+		script.assert_shown("n (default: 5) ");
+		assert_eq!(Prompt::new("Continue? ").default(true).try_read_with(&mut term, basics::YesNoInput).ok(), Some(true));
+		script.assert_shown("(default: Yes)");
+	}
+	
+	#[test]
+	fn empty_lines_are_not_eof() {
+		let script = ScriptedTerminal::new(&[""]);
+		let mut term = script.terminal();
+		assert_eq!(term.read_line().ok().as_deref(), Some(""));
+		let output = term.read_line();
+		assert!(matches!(output, Err(SmartReadError::Eof)), "{output:?}");
+	}
+	
+	#[test]
+	fn invalid_inputs_then_eof_are_errors() {
+		let script = ScriptedTerminal::new(&["", " "]);
+		let output = basics::NonWhitespaceInput.try_read_with(&mut script.terminal(), None, None);
+		assert!(matches!(output, Err(SmartReadError::Eof)), "{output:?}");
+		assert_eq!(script.used_answers(), 2);
+	}
+	
+	#[test]
+	fn every_builtin_input_returns_eof() {
+		let script = ScriptedTerminal::new::<&str>(&[]);
+		let mut term = script.terminal();
+		let output = basics::NonEmptyInput.try_read_with(&mut term, None, None);
+		assert!(matches!(output, Err(SmartReadError::Eof)), "{output:?}");
+		let output = UsizeInput.try_read_with(&mut term, None, None);
+		assert!(matches!(output, Err(SmartReadError::Eof)), "{output:?}");
+		let output = (1..=5).try_read_with(&mut term, None, None);
+		assert!(matches!(output, Err(SmartReadError::Eof)), "{output:?}");
+		let output = ["red", "green"].try_read_with(&mut term, None, None);
+		assert!(matches!(output, Err(SmartReadError::Eof)), "{output:?}");
+	}
+	
+	// like stdin when it's piped, so reads are non-interactive unless that's turned off
+	struct PipedInput(&'static [u8]);
+	
+	impl InputSource for PipedInput {
+		fn next_line(&mut self) -> Result<Option<String>, std::io::Error> {
+			self.0.next_line()
+		}
+		fn is_interactive(&self) -> bool {
+			false
+		}
+	}
+	
+	#[test]
+	fn non_interactive_reads_use_the_default() {
+		let script = ScriptedTerminal::new(&["5"]);
+		let output = Prompt::new("n: ").default(3_usize).non_interactive(true).try_read_with(&mut script.terminal(), UsizeInput);
+		assert_eq!(output.ok(), Some(3));
+		assert_eq!(script.remaining_answers(), 1);
+		script.assert_shown("Non-interactive, using the default value");
+	}
+	
+	#[test]
+	fn non_interactive_reads_without_a_default_are_errors() {
+		let script = ScriptedTerminal::new(&["5"]);
+		let output = Prompt::new("Port:\n> ").non_interactive(true).try_read_with(&mut script.terminal(), UsizeInput);
+		assert!(matches!(&output, Err(SmartReadError::NonInteractive {prompt}) if prompt == "Port:"), "{output:?}");
+		assert_eq!(script.remaining_answers(), 1);
+	}
+	
+	#[test]
+	fn non_interactive_mode_is_detected_from_the_input() {
+		let mut term = Terminal::new(PipedInput(b"5\n"), std::io::sink());
+		assert!(term.is_non_interactive());
+		assert_eq!(Prompt::new("n: ").default(3_usize).try_read_with(&mut term, UsizeInput).ok(), Some(3));
+		term.non_interactive = Some(false);
+		assert_eq!(Prompt::new("n: ").default(3_usize).try_read_with(&mut term, UsizeInput).ok(), Some(5));
+	}
+	
+	#[test]
+	fn empty_inputs_use_the_default() {
+		let script = ScriptedTerminal::new(&["", "", ""]);
+		let mut term = script.terminal();
+		let output = Prompt::new("name: ").default("anon").try_read_with(&mut term, basics::NonEmptyInput);
+		assert_eq!(output.ok().as_deref(), Some("anon"));
+		let output = Prompt::new("name: ").default("anon").try_read_with(&mut term, basics::NonWhitespaceInput);
+		assert_eq!(output.ok().as_deref(), Some("anon"));
+		let output = Prompt::new("name: ").default("anon").try_read_with(&mut term, ());
+		assert_eq!(output.ok().as_deref(), Some("anon"));
+		assert_eq!(script.output().matches("name: (default: anon) \n").count(), 3, "{}", script.output());
+	}
+	
+	#[test]
+	fn prompt_loop_retries_invalid_inputs() {
+		let script = ScriptedTerminal::new(&["abc", "7"]);
+		let output = run_prompt_loop(&mut script.terminal(), &UsizeInput, Some(String::from("n: ")), None);
+		assert_eq!(output.ok(), Some(7));
+		script.assert_shown("abc\n\nCould not parse input");
+		assert_eq!(script.output().matches("n: ").count(), 2);
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn default_prompts_end_with_a_space() {
+		let script = ScriptedTerminal::new(&["9"]);
+		let output = (5..).try_read_with(&mut script.terminal(), None, Some(6));
+		assert_eq!(output.ok(), Some(9));
+		script.assert_shown("Enter a number which is at least 5: (default: 6) 9\n");
+	}
+	
+	#[test]
+	fn themes_style_prompts_errors_and_notes() {
+		let script = ScriptedTerminal::new(&["abc", ""]);
+		let mut term = script.terminal();
+		term.theme = Theme::colored();
+		let output = Prompt::new("n: ").default(3_usize).try_read_with(&mut term, UsizeInput);
+		assert_eq!(output.ok(), Some(3));
+		script.assert_shown("\x1b[1mn: (default: 3) \x1b[0m");
+		script.assert_shown("\x1b[31mCould not parse input");
+		let output = Prompt::new("n: ").default(3_usize).non_interactive(true).try_read_with(&mut term, UsizeInput);
+		assert_eq!(output.ok(), Some(3));
+		script.assert_shown("\x1b[2mNon-interactive, using the default value\x1b[0m\n");
+	}
+	
+	#[test]
+	fn prompter_settings_apply_to_every_read() {
+		let script = ScriptedTerminal::new(&["abc", "4"]);
+		let mut prompter: Prompter = script.terminal();
+		prompter.retry_policy = Some(RetryPolicy::max_attempts(1));
+		let output = prompter.try_prompt("n: ", UsizeInput);
+		assert!(matches!(output, Err(SmartReadError::RetriesExhausted {attempts: 1})), "{output:?}");
+		assert_eq!(prompter.try_read(UsizeInput).ok(), Some(4));
+		// the default theme doesn't add any styles
+		script.assert_not_shown("\x1b[");
+	}
 }
@@ -0,0 +1,853 @@
+use crate::*;
+use completion::Completer;
+use history::History;
+use std::{ops::Range, rc::Rc, time::Instant};
+
+
+
+/// Reads lines from stdin with a line editor, which adds cursor movement and emacs-style editing keys
+/// 
+/// This works with every `TryRead` type, since it only changes how lines are typed. Use `LineEditor::terminal()` to create a terminal that uses it:
+/// 
+/// ```no_run
+/// use smart_read::prelude::*;
+/// use smart_read::line_editor::LineEditor;
+/// 
+/// set_default_terminal(LineEditor::new().terminal());
+/// let name = prompt!("Enter your name: "; NonEmptyInput);
+/// ```
+/// 
+/// These keys are supported:
+/// 
+/// | Key | Action |
+/// | --- | --- |
+/// | Left / Ctrl-B, Right / Ctrl-F | Move one character |
+/// | Ctrl-Left / Alt-B, Ctrl-Right / Alt-F | Move one word |
+/// | Home / Ctrl-A, End / Ctrl-E | Move to the start or end of the line |
+/// | Backspace / Ctrl-H, Delete / Ctrl-D | Delete one character |
+/// | Ctrl-W | Delete the previous word, up to whitespace |
+/// | Alt-Backspace, Alt-D | Delete the previous or next word, up to whitespace or punctuation |
+/// | Ctrl-U, Ctrl-K | Delete to the start or end of the line |
+/// | Ctrl-Y | Insert the last deleted text |
+/// | Up / Ctrl-P, Down / Ctrl-N | Show an earlier or later answer to the same prompt |
+/// | Ctrl-R | Search earlier answers to the same prompt, press it again for older matches, or press Esc / Ctrl-G to cancel |
+/// | Tab | Complete the line as far as possible, press it again to list the completions |
+/// | Ctrl-L | Clear the screen |
+/// | Ctrl-D (on an empty line) | End the input |
+/// | Esc | Cancel the read, which returns `SmartReadError::Cancelled` |
+/// 
+/// Answers are kept in memory, or in a file if one is given with `with_history()` (see `History`)
+/// 
+/// Completions come from the prompt's completer (see `Completer`), and lists of `InputOption`s complete their names by default
+/// 
+/// The editor puts the terminal in raw mode while a line is typed, which is only supported on Linux (on x86, ARM, RISC-V, LoongArch, and s390x) when stdin and stdout are both terminals. Otherwise, lines are read the same way as `StdinSource`
+/// 
+/// While a secret is read (see `SecretInput`), each character is shown as `*`
+#[derive(Clone, Default)]
+pub struct LineEditor {
+	state: Rc<RefCell<EditorState>>,
+}
+
+#[derive(Default)]
+struct EditorState {
+	// everything written since the last newline, which is redrawn along with the typed text
+	current_line: String,
+	// the last text that was deleted with Ctrl-W, Ctrl-U, etc, for Ctrl-Y
+	kill_buffer: String,
+	mask_input: bool,
+	history: History,
+	// the prompt id given by `set_prompt_key()`, which answers are grouped by
+	prompt_key: String,
+	completer: Option<Rc<dyn Completer>>,
+}
+
+impl LineEditor {
+	/// Basic initializer
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Sets where earlier answers are kept, by default they're only kept in memory
+	pub fn with_history(self, history: History) -> Self {
+		self.state.borrow_mut().history = history;
+		self
+	}
+	/// Returns the earlier answers that can be recalled
+	pub fn history(&self) -> History {
+		self.state.borrow().history.clone()
+	}
+	/// Creates a `Terminal` which reads from stdin with this editor and writes to stdout
+	/// 
+	/// Any number of terminals can be created, and they all share the same editor state (such as the text for Ctrl-Y)
+	pub fn terminal(&self) -> Terminal<'static> {
+		Terminal::new(EditorInput(self.state.clone()), EditorOutput(self.state.clone()))
+	}
+}
+
+
+
+fn is_raw_mode_available() -> bool {
+	tty::RAW_MODE_SUPPORTED && std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+fn write_stdout(text: &str) -> Result<(), std::io::Error> {
+	let mut stdout = std::io::stdout().lock();
+	stdout.write_all(text.as_bytes())?;
+	stdout.flush()
+}
+
+// puts the terminal back in normal mode when it's dropped, so that it's restored even if the read panics
+struct RawModeGuard;
+
+impl Drop for RawModeGuard {
+	fn drop(&mut self) {
+		let _ = tty::set_stdin_raw(false);
+	}
+}
+
+
+
+struct EditorInput(Rc<RefCell<EditorState>>);
+
+impl InputSource for EditorInput {
+	fn next_line(&mut self) -> Result<Option<String>, std::io::Error> {
+		self.edit_line(None)
+	}
+	fn is_interactive(&self) -> bool {
+		StdinSource.is_interactive()
+	}
+	fn next_line_timeout(&mut self, timeout: Duration) -> Result<Option<String>, std::io::Error> {
+		self.edit_line(Some(timeout))
+	}
+	fn set_echo(&mut self, enabled: bool) -> Result<bool, std::io::Error> {
+		if !is_raw_mode_available() {return StdinSource.set_echo(enabled);}
+		self.0.borrow_mut().mask_input = !enabled;
+		Ok(true)
+	}
+	fn set_prompt_key(&mut self, key: &str) {
+		self.0.borrow_mut().prompt_key = key.to_string();
+	}
+	fn set_completer(&mut self, completer: Option<Rc<dyn Completer>>) {
+		self.0.borrow_mut().completer = completer;
+	}
+}
+
+impl EditorInput {
+	fn edit_line(&mut self, timeout: Option<Duration>) -> Result<Option<String>, std::io::Error> {
+		if !is_raw_mode_available() || !tty::set_stdin_raw(true)? {
+			return match timeout {
+				Some(timeout) => StdinSource.next_line_timeout(timeout),
+				None => StdinSource.next_line(),
+			};
+		}
+		let _raw_mode = RawModeGuard;
+		let mut state = self.0.borrow_mut();
+		let mut line = EditedLine::new(state.current_line.clone(), state.mask_input);
+		line.redraw()?;
+		let deadline = timeout.map(|timeout| Instant::now() + timeout);
+		// secrets can't be recalled, and they're never added
+		let history = if line.mask {vec!()} else {state.history.entries(&state.prompt_key)};
+		// which answer from the history is shown, `None` means the typed line (which is kept in `draft`)
+		let mut history_index: Option<usize> = None;
+		let mut draft = String::new();
+		let mut search: Option<HistorySearch> = None;
+		let mut prev_key_was_tab = false;
+		loop {
+			
+			let timeout = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+			let key = match read_key(timeout) {
+				Ok(Some(key)) => key,
+				// the end of stdin finishes the line, or ends the input if nothing was typed
+				Ok(None) if line.text.is_empty() => Key::Ctrl('d'),
+				Ok(None) => Key::Enter,
+				Err(err) => {
+					if err.kind() == std::io::ErrorKind::TimedOut {line.move_to_end()?;}
+					return Err(err);
+				}
+			};
+			// pressing Tab twice in a row lists the completions
+			let is_second_tab = prev_key_was_tab && matches!(key, Key::Tab);
+			prev_key_was_tab = matches!(key, Key::Tab);
+			
+			if let Some(active_search) = &mut search {
+				match active_search.handle_key(&key, &history) {
+					SearchAction::Update => {
+						active_search.show(&mut line, &history);
+						line.redraw()?;
+						continue;
+					}
+					SearchAction::Cancel => {
+						line.text = std::mem::take(&mut active_search.original_text);
+						line.cursor = line.text.len();
+						line.label.clear();
+						search = None;
+						line.redraw()?;
+						continue;
+					}
+					// any other key keeps the found answer, and is then handled as usual (so Enter submits it, Left moves the cursor, etc)
+					SearchAction::Accept => {
+						line.label.clear();
+						search = None;
+						history_index = None;
+						line.redraw()?;
+					}
+				}
+			}
+			
+			match key {
+				Key::Enter => {
+					line.move_to_end()?;
+					// when echo is off, the terminal shows the newline itself (see `Terminal::prompt_secret_line()`)
+					if !line.mask {
+						write_stdout("\r\n")?;
+						state.current_line.clear();
+						// the answer is still given if the history can't be saved, since the history is only a convenience
+						let _ = state.history.add(&state.prompt_key, &line.text);
+					}
+					return Ok(Some(std::mem::take(&mut line.text)));
+				}
+				Key::Ctrl('d') if line.text.is_empty() => {
+					write_stdout("\r\n")?;
+					state.current_line.clear();
+					return Ok(None);
+				}
+				Key::Escape => {
+					line.move_to_end()?;
+					write_stdout("\r\n")?;
+					state.current_line.clear();
+					return Err(std::io::Error::other(SmartReadError::Cancelled));
+				}
+				Key::Char(c) => {
+					if line.mask {secret::reserve_wiped(&mut line.text, c.len_utf8());}
+					line.text.insert(line.cursor, c);
+					line.cursor += c.len_utf8();
+				}
+				Key::Home | Key::Ctrl('a') => line.cursor = 0,
+				Key::End | Key::Ctrl('e') => line.cursor = line.text.len(),
+				Key::Left | Key::Ctrl('b') => line.cursor = line.prev_char_start(),
+				Key::Right | Key::Ctrl('f') => line.cursor = line.next_char_end(),
+				Key::WordLeft | Key::Alt('b') => line.cursor = line.prev_word_start(char::is_alphanumeric),
+				Key::WordRight | Key::Alt('f') => line.cursor = line.next_word_end(),
+				Key::Backspace => line.delete(line.prev_char_start()..line.cursor),
+				Key::Delete | Key::Ctrl('d') => line.delete(line.cursor..line.next_char_end()),
+				Key::Ctrl('w') => line.kill(line.prev_word_start(|c| !c.is_whitespace())..line.cursor, &mut state.kill_buffer),
+				Key::AltBackspace => line.kill(line.prev_word_start(char::is_alphanumeric)..line.cursor, &mut state.kill_buffer),
+				Key::Alt('d') => line.kill(line.cursor..line.next_word_end(), &mut state.kill_buffer),
+				Key::Ctrl('u') => line.kill(0..line.cursor, &mut state.kill_buffer),
+				Key::Ctrl('k') => line.kill(line.cursor..line.text.len(), &mut state.kill_buffer),
+				Key::Ctrl('y') if !line.mask => {
+					line.text.insert_str(line.cursor, &state.kill_buffer);
+					line.cursor += state.kill_buffer.len();
+				}
+				Key::Up | Key::Ctrl('p') => {
+					let index = match history_index {
+						Some(index) => index.saturating_sub(1),
+						None if history.is_empty() => continue,
+						None => {
+							draft = std::mem::take(&mut line.text);
+							history.len() - 1
+						}
+					};
+					history_index = Some(index);
+					line.text = history[index].clone();
+					line.cursor = line.text.len();
+				}
+				Key::Down | Key::Ctrl('n') => {
+					let Some(index) = history_index else {continue;};
+					if index + 1 < history.len() {
+						history_index = Some(index + 1);
+						line.text = history[index + 1].clone();
+					} else {
+						history_index = None;
+						line.text = std::mem::take(&mut draft);
+					}
+					line.cursor = line.text.len();
+				}
+				Key::Ctrl('r') if !line.mask => {
+					let new_search = HistorySearch {
+						query: String::new(),
+						found: None,
+						original_text: line.text.clone(),
+					};
+					new_search.show(&mut line, &history);
+					search = Some(new_search);
+				}
+				Key::Tab if !line.mask => {
+					let Some(completer) = state.completer.clone() else {continue;};
+					match tab_action(&*completer, &line.text, is_second_tab) {
+						TabAction::Complete(text) => {
+							line.text = text;
+							line.cursor = line.text.len();
+						}
+						TabAction::List(completions) => {
+							line.move_to_end()?;
+							write_stdout(&completion_list(&completions, tty::terminal_width().unwrap_or(80)))?;
+							// the prompt is drawn again below the list
+							line.cursor_row = 0;
+						}
+						TabAction::Nothing => continue,
+					}
+				}
+				Key::Ctrl('l') => {
+					write_stdout("\x1b[H\x1b[2J")?;
+					line.cursor_row = 0;
+				}
+				_ => continue,
+			}
+			line.redraw()?;
+			
+		}
+	}
+}
+
+
+
+struct EditorOutput(Rc<RefCell<EditorState>>);
+
+impl OutputSink for EditorOutput {
+	fn write_text(&mut self, text: &str) -> Result<(), std::io::Error> {
+		let mut state = self.0.borrow_mut();
+		match text.rfind('\n') {
+			Some(i) => state.current_line = text[i + 1..].to_string(),
+			None => state.current_line += text,
+		}
+		std::io::stdout().write_text(text)
+	}
+	fn flush_output(&mut self) -> Result<(), std::io::Error> {
+		std::io::stdout().flush()
+	}
+}
+
+
+
+// the line that's being typed, and everything needed to redraw it
+struct EditedLine {
+	text: String,
+	// byte index into `text`
+	cursor: usize,
+	// the text before the typed text on the same line, which is usually the end of the prompt
+	prompt: String,
+	// shown between the prompt and the typed text, this is used for Ctrl-R
+	label: String,
+	mask: bool,
+	// which row the cursor is on, counting from the row that `prompt` starts on
+	cursor_row: usize,
+}
+
+impl EditedLine {
+	fn new(prompt: String, mask: bool) -> Self {
+		let prompt_width = text_width(&prompt);
+		let columns = tty::terminal_width().unwrap_or(80);
+		Self {
+			text: String::new(),
+			cursor: 0,
+			prompt,
+			label: String::new(),
+			mask,
+			// a prompt that ends at the edge of the screen leaves the cursor on its last row
+			cursor_row: prompt_width.saturating_sub(1) / columns,
+		}
+	}
+
+	fn shown_width(&self, text: &str) -> usize {
+		if self.mask {text.chars().count()} else {text_width(text)}
+	}
+
+	// reprints the prompt and the typed text, then moves the cursor to where it should be
+	fn redraw(&mut self) -> Result<(), std::io::Error> {
+		let columns = tty::terminal_width().unwrap_or(80);
+		let prompt_width = text_width(&self.prompt) + text_width(&self.label);
+		let end = prompt_width + self.shown_width(&self.text);
+		let position = prompt_width + self.shown_width(&self.text[..self.cursor]);
+		let mut output = String::new();
+		if self.cursor_row > 0 {output += &format!("\x1b[{}A", self.cursor_row);}
+		output += "\r\x1b[J";
+		output += &self.prompt;
+		output += &self.label;
+		if self.mask {
+			output += &"*".repeat(self.text.chars().count());
+		} else {
+			output += &self.text;
+		}
+		// when the text ends exactly at the edge of the screen, the cursor stays on that row until something else is printed, so it's moved down manually
+		if end > 0 && end.is_multiple_of(columns) {output += "\r\n";}
+		let (end_row, row, column) = (end / columns, position / columns, position % columns);
+		if end_row > row {output += &format!("\x1b[{}A", end_row - row);}
+		output += "\r";
+		if column > 0 {output += &format!("\x1b[{column}C");}
+		self.cursor_row = row;
+		write_stdout(&output)
+	}
+
+	fn move_to_end(&mut self) -> Result<(), std::io::Error> {
+		self.cursor = self.text.len();
+		self.redraw()
+	}
+
+	// characters with no width (like combining accents) are moved over along with the character before them
+	fn prev_char_start(&self) -> usize {
+		let mut position = self.cursor;
+		while let Some(c) = self.text[..position].chars().next_back() {
+			position -= c.len_utf8();
+			if char_width(c) > 0 {break;}
+		}
+		position
+	}
+
+	fn next_char_end(&self) -> usize {
+		let mut chars = self.text[self.cursor..].chars();
+		let Some(c) = chars.next() else {return self.cursor;};
+		let mut position = self.cursor + c.len_utf8();
+		for c in chars.take_while(|c| char_width(*c) == 0) {
+			position += c.len_utf8();
+		}
+		position
+	}
+
+	fn prev_word_start(&self, is_word_char: fn(char) -> bool) -> usize {
+		let before_word = self.text[..self.cursor].trim_end_matches(|c| !is_word_char(c));
+		before_word.trim_end_matches(is_word_char).len()
+	}
+
+	fn next_word_end(&self) -> usize {
+		let after_word = self.text[self.cursor..].trim_start_matches(|c: char| !c.is_alphanumeric());
+		self.text.len() - after_word.trim_start_matches(char::is_alphanumeric).len()
+	}
+
+	fn delete(&mut self, range: Range<usize>) {
+		self.cursor = range.start;
+		self.text.replace_range(range, "");
+	}
+
+	// same as `delete()`, but the deleted text can be inserted again with Ctrl-Y (unless it's a secret)
+	fn kill(&mut self, range: Range<usize>, kill_buffer: &mut String) {
+		if range.is_empty() {return;}
+		if !self.mask {
+			*kill_buffer = self.text[range.clone()].to_string();
+		}
+		self.delete(range);
+	}
+}
+
+// a secret that's being typed is wiped if the read fails
+impl Drop for EditedLine {
+	fn drop(&mut self) {
+		if self.mask {
+			drop(SecretString::from(std::mem::take(&mut self.text)));
+		}
+	}
+}
+
+
+
+// Ctrl-R, which searches the history for answers that contain `query`
+struct HistorySearch {
+	query: String,
+	// which answer from the history matches, if any
+	found: Option<usize>,
+	// the line from before the search, which is put back if the search is cancelled
+	original_text: String,
+}
+
+enum SearchAction {
+	Update,
+	Cancel,
+	Accept,
+}
+
+impl HistorySearch {
+	fn handle_key(&mut self, key: &Key, history: &[String]) -> SearchAction {
+		match key {
+			Key::Char(c) => {
+				self.query.push(*c);
+				self.found = self.find_before(history, history.len());
+			}
+			Key::Backspace => {
+				self.query.pop();
+				self.found = self.find_before(history, history.len());
+			}
+			// finds an older match, or keeps the current one if there are no older matches
+			Key::Ctrl('r') => {
+				let before = self.found.unwrap_or(history.len());
+				self.found = self.find_before(history, before).or(self.found);
+			}
+			Key::Escape | Key::Ctrl('g') => return SearchAction::Cancel,
+			_ => return SearchAction::Accept,
+		}
+		SearchAction::Update
+	}
+	// finds the newest answer before the given index that contains the query
+	fn find_before(&self, history: &[String], before: usize) -> Option<usize> {
+		history[..before].iter().rposition(|entry| entry.contains(&self.query))
+	}
+	fn show(&self, line: &mut EditedLine, history: &[String]) {
+		let status = if self.found.is_none() && !self.query.is_empty() {"failed search"} else {"search"};
+		line.label = format!("({status}) `{}': ", self.query);
+		if let Some(found) = self.found {
+			line.text = history[found].clone();
+			line.cursor = line.text.find(&self.query).unwrap_or(0);
+		}
+	}
+}
+
+
+
+#[derive(Debug, PartialEq)]
+enum Key {
+	Char(char),
+	Ctrl(char),
+	Alt(char),
+	Escape,
+	Enter,
+	Tab,
+	Backspace,
+	AltBackspace,
+	Delete,
+	Left,
+	Right,
+	Up,
+	Down,
+	WordLeft,
+	WordRight,
+	Home,
+	End,
+	Other,
+}
+
+// how long to wait for the rest of an escape sequence, so that pressing Esc by itself doesn't block
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(50);
+
+// reads one byte, waiting for at most the given amount of time, `None` means that the end of the input was reached
+type ReadByte<'a> = &'a mut dyn FnMut(Option<Duration>) -> Result<Option<u8>, std::io::Error>;
+
+// `None` means that the end of stdin was reached
+fn read_key(timeout: Option<Duration>) -> Result<Option<Key>, std::io::Error> {
+	decode_key(&mut tty::read_stdin_byte, timeout)
+}
+
+fn decode_key(read_byte: ReadByte, timeout: Option<Duration>) -> Result<Option<Key>, std::io::Error> {
+	let Some(byte) = read_byte(timeout)? else {return Ok(None);};
+	let key = match byte {
+		b'\r' | b'\n' => Key::Enter,
+		b'\t' => Key::Tab,
+		0x7F | 0x08 => Key::Backspace,
+		0x1B => read_escape_sequence(read_byte)?,
+		0x01..=0x1A => Key::Ctrl(char::from(byte - 1 + b'a')),
+		0x20..=0x7E => Key::Char(char::from(byte)),
+		0x80.. => read_utf8_char(byte, read_byte)?,
+		_ => Key::Other,
+	};
+	Ok(Some(key))
+}
+
+// `None` means that the escape sequence ended early
+fn read_escape_byte(read_byte: ReadByte) -> Result<Option<u8>, std::io::Error> {
+	match read_byte(Some(ESCAPE_TIMEOUT)) {
+		Err(err) if err.kind() == std::io::ErrorKind::TimedOut => Ok(None),
+		result => result,
+	}
+}
+
+fn read_escape_sequence(read_byte: ReadByte) -> Result<Key, std::io::Error> {
+	let Some(byte) = read_escape_byte(read_byte)? else {return Ok(Key::Escape);};
+	if byte != b'[' && byte != b'O' {
+		return Ok(match byte {
+			0x7F | 0x08 => Key::AltBackspace,
+			0x20..=0x7E => Key::Alt(char::from(byte)),
+			_ => Key::Other,
+		});
+	}
+	let mut params = String::new();
+	let final_byte = loop {
+		let Some(byte) = read_escape_byte(read_byte)? else {return Ok(Key::Other);};
+		if (0x40..=0x7E).contains(&byte) {break byte;}
+		params.push(char::from(byte));
+	};
+	Ok(match (params.as_str(), final_byte) {
+		("", b'A') => Key::Up,
+		("", b'B') => Key::Down,
+		("", b'C') => Key::Right,
+		("", b'D') => Key::Left,
+		("1;5" | "1;3", b'C') => Key::WordRight,
+		("1;5" | "1;3", b'D') => Key::WordLeft,
+		("", b'H') | ("1" | "7", b'~') => Key::Home,
+		("", b'F') | ("4" | "8", b'~') => Key::End,
+		("3", b'~') => Key::Delete,
+		_ => Key::Other,
+	})
+}
+
+fn read_utf8_char(first_byte: u8, read_byte: ReadByte) -> Result<Key, std::io::Error> {
+	let len = match first_byte {
+		0xC0..=0xDF => 2,
+		0xE0..=0xEF => 3,
+		0xF0..=0xF7 => 4,
+		_ => return Ok(Key::Other),
+	};
+	let mut bytes = [first_byte, 0, 0, 0];
+	for byte in &mut bytes[1..len] {
+		let Some(next_byte) = read_byte(None)? else {return Ok(Key::Other);};
+		*byte = next_byte;
+	}
+	let c = std::str::from_utf8(&bytes[..len]).ok().and_then(|text| text.chars().next());
+	Ok(c.map_or(Key::Other, Key::Char))
+}
+
+
+
+// what pressing Tab does to the typed text
+enum TabAction {
+	Complete(String),
+	List(Vec<String>),
+	Nothing,
+}
+
+fn tab_action(completer: &dyn Completer, text: &str, is_second_tab: bool) -> TabAction {
+	let mut completions: Vec<String> = vec!();
+	for completion in completer.complete(text) {
+		if !completions.contains(&completion) {completions.push(completion);}
+	}
+	let completed = common_start(&completions);
+	if completed != text && (completions.len() == 1 || completed.len() >= text.len()) {
+		TabAction::Complete(completed.to_string())
+	} else if is_second_tab && !completions.is_empty() {
+		TabAction::List(completions)
+	} else {
+		TabAction::Nothing
+	}
+}
+
+// the longest text that every completion starts with
+fn common_start(completions: &[String]) -> &str {
+	let Some((first, rest)) = completions.split_first() else {return "";};
+	let len = rest.iter().fold(first.len(), |len, completion| {
+		first[..len].char_indices().zip(completion.chars())
+			.find(|((_, a), b)| a != b)
+			.map_or(len.min(completion.len()), |((i, _), _)| i)
+	});
+	&first[..len]
+}
+
+// lays out the completions in columns, starting on the row below the typed line
+fn completion_list(completions: &[String], columns: usize) -> String {
+	let column_width = completions.iter().map(|completion| text_width(completion)).max().unwrap_or(0) + 2;
+	let mut output = String::from("\r\n");
+	for row in completions.chunks((columns / column_width).max(1)) {
+		for (i, completion) in row.iter().enumerate() {
+			output += completion;
+			if i + 1 < row.len() {
+				output += &" ".repeat(column_width - text_width(completion));
+			}
+		}
+		output += "\r\n";
+	}
+	output
+}
+
+// how many columns the text takes up, ignoring ANSI escape codes (like the ones added by `Theme`)
+fn text_width(text: &str) -> usize {
+	let mut width = 0;
+	let mut chars = text.chars();
+	while let Some(c) = chars.next() {
+		if c != '\x1b' {
+			width += char_width(c);
+			continue;
+		}
+		if chars.next() == Some('[') {
+			for c in chars.by_ref() {
+				if ('\x40'..='\x7E').contains(&c) {break;}
+			}
+		}
+	}
+	width
+}
+
+fn char_width(c: char) -> usize {
+	let c = c as u32;
+	let is_in = |ranges: &[(u32, u32)]| ranges.iter().any(|&(start, end)| (start..=end).contains(&c));
+	if c < 0x20 || (0x7F..0xA0).contains(&c) || is_in(ZERO_WIDTH_CHARS) {return 0;}
+	if is_in(WIDE_CHARS) {return 2;}
+	1
+}
+
+// combining marks, zero-width spaces and joiners, and variation selectors
+const ZERO_WIDTH_CHARS: &[(u32, u32)] = &[
+	(0x0300, 0x036F), (0x0483, 0x0489), (0x0591, 0x05BD), (0x0610, 0x061A), (0x064B, 0x065F),
+	(0x1160, 0x11FF), (0x1AB0, 0x1AFF), (0x1DC0, 0x1DFF), (0x200B, 0x200F), (0x2060, 0x2064),
+	(0x20D0, 0x20FF), (0xFE00, 0xFE0F), (0xFE20, 0xFE2F), (0xFEFF, 0xFEFF), (0xE0100, 0xE01EF),
+];
+
+// east asian wide and fullwidth characters, and emoji
+const WIDE_CHARS: &[(u32, u32)] = &[
+	(0x1100, 0x115F), (0x231A, 0x231B), (0x2329, 0x232A), (0x23E9, 0x23EC), (0x23F0, 0x23F0),
+	(0x23F3, 0x23F3), (0x25FD, 0x25FE), (0x2614, 0x2615), (0x2648, 0x2653), (0x267F, 0x267F),
+	(0x2693, 0x2693), (0x26A1, 0x26A1), (0x26AA, 0x26AB), (0x26BD, 0x26BE), (0x26C4, 0x26C5),
+	(0x26CE, 0x26CE), (0x26D4, 0x26D4), (0x26EA, 0x26EA), (0x26F2, 0x26F3), (0x26F5, 0x26F5),
+	(0x26FA, 0x26FA), (0x26FD, 0x26FD), (0x2705, 0x2705), (0x270A, 0x270B), (0x2728, 0x2728),
+	(0x274C, 0x274C), (0x274E, 0x274E), (0x2753, 0x2755), (0x2757, 0x2757), (0x2795, 0x2797),
+	(0x27B0, 0x27B0), (0x27BF, 0x27BF), (0x2B1B, 0x2B1C), (0x2B50, 0x2B50), (0x2B55, 0x2B55),
+	(0x2E80, 0x303E), (0x3041, 0x33FF), (0x3400, 0x4DBF), (0x4E00, 0x9FFF), (0xA000, 0xA4CF),
+	(0xA960, 0xA97F), (0xAC00, 0xD7A3), (0xF900, 0xFAFF), (0xFE10, 0xFE19), (0xFE30, 0xFE6F),
+	(0xFF00, 0xFF60), (0xFFE0, 0xFFE6), (0x16FE0, 0x16FE4), (0x17000, 0x18CFF), (0x1B000, 0x1B2FF),
+	(0x1F004, 0x1F004), (0x1F0CF, 0x1F0CF), (0x1F18E, 0x1F18E), (0x1F191, 0x1F19A), (0x1F200, 0x1F251),
+	(0x1F300, 0x1F64F), (0x1F680, 0x1F6FF), (0x1F7E0, 0x1F7EB), (0x1F90C, 0x1F9FF), (0x1FA70, 0x1FAFF),
+	(0x20000, 0x2FFFD), (0x30000, 0x3FFFD),
+];
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn tab_completes_the_common_start() {
+		let hosts = vec!("web-1.example.com", "web-2.example.com", "db.example.com");
+		assert!(matches!(tab_action(&hosts, "w", false), TabAction::Complete(text) if text == "web-"));
+		assert!(matches!(tab_action(&hosts, "WEB", false), TabAction::Complete(text) if text == "web-"));
+		assert!(matches!(tab_action(&hosts, "d", false), TabAction::Complete(text) if text == "db.example.com"));
+		assert!(matches!(tab_action(&hosts, "x", true), TabAction::Nothing));
+	}
+	
+	#[test]
+	fn second_tab_lists_the_completions() {
+		let hosts = vec!("web-1.example.com", "web-2.example.com", "web-1.example.com");
+		assert!(matches!(tab_action(&hosts, "web-", false), TabAction::Nothing));
+		assert!(matches!(tab_action(&hosts, "web-", true), TabAction::List(completions) if completions == ["web-1.example.com", "web-2.example.com"]));
+	}
+	
+	#[test]
+	fn common_start_stops_at_characters() {
+		assert_eq!(common_start(&[]), "");
+		assert_eq!(common_start(&[String::from("héllo"), String::from("hélp")]), "hél");
+		assert_eq!(common_start(&[String::from("hé"), String::from("hè")]), "h");
+		assert_eq!(common_start(&[String::from("abc"), String::from("ab")]), "ab");
+	}
+	
+	#[test]
+	fn completions_are_listed_in_columns() {
+		let completions = ["a", "bb", "ccc"].map(String::from);
+		assert_eq!(completion_list(&completions, 10), "\r\na    bb\r\nccc\r\n");
+		assert_eq!(completion_list(&completions, 3), "\r\na\r\nbb\r\nccc\r\n");
+	}
+	
+	// decodes the first key from the given bytes, running out of bytes is the same as the rest of an escape sequence timing out
+	fn decode(bytes: &[u8]) -> Option<Key> {
+		let mut bytes = bytes.iter().copied();
+		let mut read_byte = |_timeout: Option<Duration>| bytes.next().map(Some).ok_or_else(|| std::io::Error::from(std::io::ErrorKind::TimedOut));
+		decode_key(&mut read_byte, None).ok().flatten()
+	}
+	
+	#[test]
+	fn keys_are_decoded() {
+		assert_eq!(decode(b"a"), Some(Key::Char('a')));
+		assert_eq!(decode(b"\r"), Some(Key::Enter));
+		assert_eq!(decode(b"\n"), Some(Key::Enter));
+		assert_eq!(decode(b"\t"), Some(Key::Tab));
+		assert_eq!(decode(b"\x7F"), Some(Key::Backspace));
+		assert_eq!(decode(b"\x01"), Some(Key::Ctrl('a')));
+		assert_eq!(decode(b"\x12"), Some(Key::Ctrl('r')));
+		assert_eq!(decode("é".as_bytes()), Some(Key::Char('é')));
+		assert_eq!(decode("😀".as_bytes()), Some(Key::Char('😀')));
+		assert_eq!(decode(b"\xFF"), Some(Key::Other));
+	}
+	
+	#[test]
+	fn escape_sequences_are_decoded() {
+		assert_eq!(decode(b"\x1B"), Some(Key::Escape));
+		assert_eq!(decode(b"\x1B[A"), Some(Key::Up));
+		assert_eq!(decode(b"\x1B[D"), Some(Key::Left));
+		assert_eq!(decode(b"\x1BOH"), Some(Key::Home));
+		assert_eq!(decode(b"\x1B[4~"), Some(Key::End));
+		assert_eq!(decode(b"\x1B[3~"), Some(Key::Delete));
+		assert_eq!(decode(b"\x1B[1;5C"), Some(Key::WordRight));
+		assert_eq!(decode(b"\x1B[1;3D"), Some(Key::WordLeft));
+		assert_eq!(decode(b"\x1Bb"), Some(Key::Alt('b')));
+		assert_eq!(decode(b"\x1B\x7F"), Some(Key::AltBackspace));
+		assert_eq!(decode(b"\x1B[1;5"), Some(Key::Other));
+		assert_eq!(decode(b"\x1B[99~"), Some(Key::Other));
+	}
+	
+	#[test]
+	fn widths_skip_escape_codes_and_count_wide_characters() {
+		assert_eq!(text_width("abc"), 3);
+		assert_eq!(text_width("\x1b[1mab\x1b[0m: "), 4);
+		assert_eq!(text_width("日本"), 4);
+		assert_eq!(text_width("e\u{301}"), 1);
+		assert_eq!(text_width("😀!"), 3);
+		assert_eq!(text_width("\x07"), 0);
+	}
+	
+	fn edited_line(text: &str, cursor: usize) -> EditedLine {
+		let mut line = EditedLine::new(String::new(), false);
+		line.text = text.to_string();
+		line.cursor = cursor;
+		line
+	}
+	
+	#[test]
+	fn cursor_moves_over_combining_characters() {
+		let line = edited_line("ae\u{301}b", 5);
+		assert_eq!(line.prev_char_start(), 4);
+		let line = edited_line("ae\u{301}b", 4);
+		assert_eq!(line.prev_char_start(), 1);
+		let line = edited_line("ae\u{301}b", 1);
+		assert_eq!(line.next_char_end(), 4);
+		let line = edited_line("ae\u{301}b", 5);
+		assert_eq!(line.next_char_end(), 5);
+	}
+	
+	#[test]
+	fn cursor_moves_over_words() {
+		let line = edited_line("cargo run --release", 19);
+		assert_eq!(line.prev_word_start(char::is_alphanumeric), 12);
+		assert_eq!(line.prev_word_start(|c| !c.is_whitespace()), 10);
+		let line = edited_line("cargo run --release", 0);
+		assert_eq!(line.next_word_end(), 5);
+		let line = edited_line("cargo run --release", 5);
+		assert_eq!(line.next_word_end(), 9);
+	}
+	
+	#[test]
+	fn killed_text_is_kept_unless_masked() {
+		let mut kill_buffer = String::new();
+		let mut line = edited_line("cargo run", 9);
+		line.kill(6..9, &mut kill_buffer);
+		assert_eq!((line.text.as_str(), line.cursor, kill_buffer.as_str()), ("cargo ", 6, "run"));
+		let mut line = edited_line("hunter2", 7);
+		line.mask = true;
+		line.kill(0..7, &mut kill_buffer);
+		assert_eq!((line.text.as_str(), kill_buffer.as_str()), ("", "run"));
+	}
+	
+	#[test]
+	fn history_search_finds_older_matches() {
+		let history = ["cargo build", "cargo test", "ls", "cargo run"].map(String::from);
+		let mut search = HistorySearch {query: String::new(), found: None, original_text: String::new()};
+		for c in ['c', 'a'] {
+			assert!(matches!(search.handle_key(&Key::Char(c), &history), SearchAction::Update));
+		}
+		assert_eq!(search.found, Some(3));
+		search.handle_key(&Key::Ctrl('r'), &history);
+		assert_eq!(search.found, Some(1));
+		search.handle_key(&Key::Ctrl('r'), &history);
+		search.handle_key(&Key::Ctrl('r'), &history);
+		assert_eq!(search.found, Some(0));
+		search.handle_key(&Key::Backspace, &history);
+		assert_eq!(search.found, Some(3));
+		assert!(matches!(search.handle_key(&Key::Escape, &history), SearchAction::Cancel));
+		assert!(matches!(search.handle_key(&Key::Left, &history), SearchAction::Accept));
+	}
+	
+	#[test]
+	fn history_search_shows_the_match() {
+		let history = ["cargo build", "ls"].map(String::from);
+		let mut search = HistorySearch {query: String::new(), found: None, original_text: String::new()};
+		let mut line = edited_line("", 0);
+		search.handle_key(&Key::Char('b'), &history);
+		search.show(&mut line, &history);
+		assert_eq!((line.label.as_str(), line.text.as_str(), line.cursor), ("(search) `b': ", "cargo build", 6));
+		search.handle_key(&Key::Char('x'), &history);
+		search.show(&mut line, &history);
+		assert_eq!((line.label.as_str(), line.text.as_str()), ("(failed search) `bx': ", "cargo build"));
+	}
+}
@@ -1,5 +1,5 @@
 use crate::*;
-use std::{collections::{LinkedList, VecDeque}, ops::Deref};
+use std::{collections::{LinkedList, VecDeque}, ops::Deref, rc::Rc};
 
 
 
@@ -7,9 +7,26 @@ use std::{collections::{LinkedList, VecDeque}, ops::Deref};
 /// 
 /// The returned `usize` is always less than the length of `input_options`
 /// 
-/// If `input_options` is empty, it will return `ListConstraintError::EmptyList`
-pub fn read_list<Data>(input_options: &[InputOption<Data>], prompt: Option<String>, default: Option<usize>) -> BoxResult<usize> {
-	if input_options.is_empty() {return Err(Box::new(ListConstraintError::EmptyList));}
+/// If `input_options` is empty, it will return `SmartReadError::EmptyList`, and if `default` is not a valid index, it will return `SmartReadError::InvalidDefault`
+pub fn read_list<Data>(term: &mut Terminal, input_options: &[InputOption<Data>], prompt: Option<String>, default: Option<usize>) -> ReadResult<usize> {
+	// the options' names are completed with Tab, unless a completer was already given
+	let added_completer = term.completer.is_none();
+	if added_completer {
+		let names = input_options.iter().flat_map(|option| option.names.iter().cloned()).collect::<Vec<_>>();
+		term.completer = Some(Rc::new(names));
+	}
+	let output = read_list_inner(term, input_options, prompt, default);
+	if added_completer {
+		term.completer = None;
+	}
+	output
+}
+
+fn read_list_inner<Data>(term: &mut Terminal, input_options: &[InputOption<Data>], prompt: Option<String>, default: Option<usize>) -> ReadResult<usize> {
+	if input_options.is_empty() {return Err(SmartReadError::EmptyList);}
+	if let Some(default) = default && default >= input_options.len() {
+		return Err(SmartReadError::InvalidDefault(format!("default index {default} is out of bounds for a list of {} options", input_options.len())));
+	}
 	
 	// get prompt data
 	let prompt = prompt.unwrap_or(String::from("Enter one of the following:"));
@@ -39,23 +56,24 @@ pub fn read_list<Data>(input_options: &[InputOption<Data>], prompt: Option<Strin
 	}
 	
 	// misc work
-	let print_prompt = || {
-		println!("{prompt}");
-		for option in display_strings.iter() {
-			println!("{option}");
-		}
-		println!();
-	};
+	let mut full_prompt = prompt + "\n";
+	for option in display_strings.iter() {
+		full_prompt += option;
+		full_prompt.push('\n');
+	}
+	full_prompt.push('\n');
 	
+	// this doesn't need any input, so it's done the same way in non-interactive mode
 	if input_options.len() == 1 {
-		print_prompt();
-		println!();
-		println!("Automatically choosing the first option because it is the only option");
+		term.print_prompt(&full_prompt)?;
+		term.println("")?;
+		term.print_note("Automatically choosing the first option because it is the only option")?;
 		return Ok(0);
 	}
 	
-	print_prompt();
-	let mut input = read_stdin()?;
+	let mut input = term.prompt_line(&full_prompt, default.is_some())?;
+	let mut retries = RetryCounter::new(term);
+	let mut default = default;
 	
 	// read input
 	loop {
@@ -71,27 +89,32 @@ pub fn read_list<Data>(input_options: &[InputOption<Data>], prompt: Option<Strin
 			}
 		}
 		
-		println!();
-		println!("Invalid option.");
+		if let Some(default) = retries.invalid_input(term, "Invalid option.", &mut default)? {
+			return Ok(default);
+		}
 		
 		// try fuzzy match
 		if let Some(possible_choose_string_index) = custom_fuzzy_search(&input, &all_choose_strings) {
 			let possible_option_index = choose_name_mappings[possible_choose_string_index];
 			let possible_option = &input_options[possible_option_index];
-			if choose_name_hidden_flags[possible_choose_string_index] {
-				print!("Did you mean to type \"{}\", for option \"{}\"? (enter nothing to confirm, or re-enter input) ", all_choose_strings[possible_choose_string_index], possible_option.get_name());
+			let confirm_prompt = if choose_name_hidden_flags[possible_choose_string_index] {
+				format!("Did you mean to type \"{}\", for option \"{}\"? (enter nothing to confirm, or re-enter input) ", all_choose_strings[possible_choose_string_index], possible_option.get_name())
 			} else {
-				print!("Did you mean \"{}\"? (enter nothing to confirm, or re-enter input) ", all_choose_strings[possible_choose_string_index]);
-			}
-			let new_input = read_stdin()?;
+				format!("Did you mean \"{}\"? (enter nothing to confirm, or re-enter input) ", all_choose_strings[possible_choose_string_index])
+			};
+			// here, an empty input confirms the suggestion instead of choosing the default, so timeouts have to be handled separately
+			let new_input = match (term.prompt_line(&confirm_prompt, false), default) {
+				(Err(SmartReadError::Timeout), Some(default)) => return Ok(default),
+				(new_input, _) => new_input?,
+			};
 			if new_input.is_empty() {
+				term.log_event(SessionEvent::SuggestionAccepted(all_choose_strings[possible_choose_string_index].to_string()))?;
 				let chosen_index = possible_option_index;
 				return Ok(chosen_index);
 			}
 			input = new_input;
 		} else {
-			print!("Invalid option, please re-enter input: ");
-			input = read_stdin()?;
+			input = term.prompt_line("Invalid option, please re-enter input: ", default.is_some())?;
 		}
 		
 	}
@@ -103,8 +126,8 @@ pub fn read_list<Data>(input_options: &[InputOption<Data>], prompt: Option<Strin
 impl<'a, Data> TryRead for &'a [InputOption<Data>] {
 	type Output = (usize, &'a InputOption<Data>);
 	type Default = usize;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
-		let chosen_index = read_list(self, prompt, default)?;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		let chosen_index = read_list(term, self, prompt, default)?;
 		Ok((chosen_index, &self[chosen_index]))
 	}
 }
@@ -114,8 +137,8 @@ impl<'a, Data> TryRead for &'a [InputOption<Data>] {
 impl<'a, Data, const LEN: usize> TryRead for &'a [InputOption<Data>; LEN] {
 	type Output = (usize, &'a InputOption<Data>);
 	type Default = usize;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
-		let chosen_index = read_list(self, prompt, default)?;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		let chosen_index = read_list(term, self, prompt, default)?;
 		Ok((chosen_index, &self[chosen_index]))
 	}
 }
@@ -124,8 +147,8 @@ impl<'a, Data, const LEN: usize> TryRead for &'a [InputOption<Data>; LEN] {
 impl<Data, const LEN: usize> TryRead for [InputOption<Data>; LEN] {
 	type Output = (usize, InputOption<Data>);
 	type Default = usize;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
-		let chosen_index = read_list(&self, prompt, default)?;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		let chosen_index = read_list(term, &self, prompt, default)?;
 		#[allow(clippy::expect_used)] // REASON: the output of read_list() is always less than the length of the given slice
 		Ok((chosen_index, self.into_iter().nth(chosen_index).expect("chosen index is out of bounds")))
 	}
@@ -134,16 +157,17 @@ impl<Data, const LEN: usize> TryRead for [InputOption<Data>; LEN] {
 
 
 /// Error type for list constraints
-/// 
-/// Right now there's only one error type, but there may be more in the future
+#[deprecated(since = "0.9.0", note = "lists now return `SmartReadError::EmptyList`, this will be removed in the next release")]
 #[derive(Debug)]
 pub enum ListConstraintError {
 	/// This exists because an empty list would be a softlock
 	EmptyList,
 }
 
+#[allow(deprecated)] // REASON: this is kept until `ListConstraintError` is removed
 impl Error for ListConstraintError {}
 
+#[allow(deprecated)] // REASON: this is kept until `ListConstraintError` is removed
 impl Display for ListConstraintError {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
@@ -201,7 +225,8 @@ pub fn custom_fuzzy_match(pattern: &str, item: &str) -> f32 {
 /// 
 /// Example:
 /// 
-/// ```
+/// ```no_run
+/// # use smart_read::prelude::*;
 /// // example data
 /// let mut colors = vec!("Red", "green", "Blue");
 /// 
@@ -235,7 +260,7 @@ pub struct InputOption<Data> {
 impl<Data> InputOption<Data> {
 	/// Basic initializer
 	pub fn new<T: ToString>(bulletin: impl ToString, names: &[T], data: Data) -> Self {
-		let names = names.into_iter().map(ToString::to_string).collect::<Vec<_>>();
+		let names = names.iter().map(ToString::to_string).collect::<Vec<_>>();
 		Self {
 			bulletin_string: Some(bulletin.to_string()),
 			names,
@@ -244,7 +269,7 @@ impl<Data> InputOption<Data> {
 	}
 	/// Initializer without bulletin string
 	pub fn new_without_bulletin<T: ToString>(names: &[T], data: Data) -> Self {
-		let names = names.into_iter().map(ToString::to_string).collect::<Vec<_>>();
+		let names = names.iter().map(ToString::to_string).collect::<Vec<_>>();
 		Self {
 			bulletin_string: None,
 			names,
@@ -278,7 +303,7 @@ impl<Data> InputOption<Data> {
 impl<'a, T: Display> TryRead for &'a [T] {
 	type Output = (usize, &'a T);
 	type Default = usize;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
 		let options = self.iter().enumerate()
 			.map(|(i, option)| {
 				InputOption {
@@ -288,7 +313,7 @@ impl<'a, T: Display> TryRead for &'a [T] {
 				}
 			})
 			.collect::<Vec<_>>();
-		let chosen_index = options.try_read_line(prompt, default)?.0;
+		let chosen_index = options.try_read_with(term, prompt, default)?.0;
 		Ok((chosen_index, &self[chosen_index]))
 	}
 }
@@ -297,7 +322,7 @@ impl<'a, T: Display> TryRead for &'a [T] {
 //impl<'a, T: Display, const LEN: usize> TryRead for &'a [T; LEN] {
 //	type Output = (usize, &'a T);
 //	type Default = usize;
-//	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
+//	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
 //		let options = self.iter().enumerate()
 //			.map(|(i, option)| {
 //				InputOption {
@@ -308,7 +333,7 @@ impl<'a, T: Display> TryRead for &'a [T] {
 //				}
 //			})
 //			.collect::<Vec<_>>();
-//		let chosen_index = options.try_read_line(prompt, default)?.0;
+//		let chosen_index = options.try_read_with(term, prompt, default)?.0;
 //		Ok((chosen_index, &self[chosen_index]))
 //	}
 //}
@@ -316,7 +341,7 @@ impl<'a, T: Display> TryRead for &'a [T] {
 impl<T: Display, const LEN: usize> TryRead for [T; LEN] {
 	type Output = (usize, T);
 	type Default = usize;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
 		let options = self.iter().enumerate()
 			.map(|(i, option)| {
 				InputOption {
@@ -326,7 +351,7 @@ impl<T: Display, const LEN: usize> TryRead for [T; LEN] {
 				}
 			})
 			.collect::<Vec<_>>();
-		let chosen_index = options.try_read_line(prompt, default)?.0;
+		let chosen_index = options.try_read_with(term, prompt, default)?.0;
 		#[allow(clippy::expect_used)] // REASON: (&[InputOption<_>]).try_Read_line().0 is always less than the length of the given vec
 		Ok((chosen_index, self.into_iter().nth(chosen_index).expect("chosen index is out of bounds")))
 	}
@@ -335,7 +360,7 @@ impl<T: Display, const LEN: usize> TryRead for [T; LEN] {
 impl<T: Display> TryRead for Vec<T> {
 	type Output = (usize, T);
 	type Default = usize;
-	fn try_read_line(mut self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
+	fn try_read_with(mut self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
 		let options = self.iter().enumerate()
 			.map(|(i, option)| {
 				InputOption {
@@ -345,7 +370,7 @@ impl<T: Display> TryRead for Vec<T> {
 				}
 			})
 			.collect::<Vec<_>>();
-		let chosen_index = options.try_read_line(prompt, default)?.0;
+		let chosen_index = options.try_read_with(term, prompt, default)?.0;
 		Ok((chosen_index, self.swap_remove(chosen_index)))
 	}
 }
@@ -353,7 +378,7 @@ impl<T: Display> TryRead for Vec<T> {
 impl<T: Display> TryRead for VecDeque<T> {
 	type Output = (usize, T);
 	type Default = usize;
-	fn try_read_line(mut self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
+	fn try_read_with(mut self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
 		let options = self.iter().enumerate()
 			.map(|(i, option)| {
 				InputOption {
@@ -363,7 +388,7 @@ impl<T: Display> TryRead for VecDeque<T> {
 				}
 			})
 			.collect::<Vec<_>>();
-		let chosen_index = options.try_read_line(prompt, default)?.0;
+		let chosen_index = options.try_read_with(term, prompt, default)?.0;
 		#[allow(clippy::expect_used)] // REASON: (&[InputOption<_>]).try_Read_line().0 is always less than the length of the given vec
 		Ok((chosen_index, self.swap_remove_back(chosen_index).expect("chosen index is out of bounds")))
 	}
@@ -372,7 +397,7 @@ impl<T: Display> TryRead for VecDeque<T> {
 impl<T: Display> TryRead for LinkedList<T> {
 	type Output = (usize, T);
 	type Default = usize;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Default>) -> BoxResult<Self::Output> {
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
 		let options = self.iter().enumerate()
 			.map(|(i, option)| {
 				InputOption {
@@ -382,7 +407,7 @@ impl<T: Display> TryRead for LinkedList<T> {
 				}
 			})
 			.collect::<Vec<_>>();
-		let chosen_index = options.try_read_line(prompt, default)?.0;
+		let chosen_index = options.try_read_with(term, prompt, default)?.0;
 		#[allow(clippy::expect_used)] // REASON: (&[InputOption<_>]).try_Read_line().0 is always less than the length of the given vec
 		Ok((chosen_index, self.into_iter().nth(chosen_index).expect("chosen index is out of bounds")))
 	}
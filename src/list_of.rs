@@ -0,0 +1,163 @@
+use crate::*;
+
+
+
+/// Reads a list of values from a single line, such as `80, 443, 8080`
+/// 
+/// Each value goes through the element type's parsing and validation, so `ListOf::new(1..=65535)` checks that every value is within the range. The element type can be anything that implements `PromptHooks`, which includes `Parse<T>` for any `FromStr` type
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// # use smart_read::prelude::*;
+/// let ports = prompt!("Enter ports: "; ListOf::new(1..=65535).min_len(1));
+/// let tags = prompt!("Enter tags, separated by spaces: "; ListOf::new(NonEmptyInput).separator(" ").dedup());
+/// ```
+#[derive(Clone)]
+pub struct ListOf<T: PromptHooks> {
+	/// The type of each value
+	pub element: T,
+	/// What the values are separated by, this is `","` by default
+	pub separator: String,
+	/// Whether whitespace around each value is removed (empty values are also skipped when this is enabled), this is `true` by default
+	pub trim: bool,
+	/// The smallest number of values allowed
+	pub min_len: Option<usize>,
+	/// The largest number of values allowed
+	pub max_len: Option<usize>,
+	/// If this is set, values that are equal to an earlier value are removed
+	pub dedup: Option<EqualityFn<T::Output>>,
+}
+
+/// A function that checks whether two values are equal
+pub type EqualityFn<T> = fn(&T, &T) -> bool;
+
+impl<T: PromptHooks> ListOf<T> {
+	/// Creates a `ListOf` with values separated by `","`
+	pub fn new(element: T) -> Self {
+		Self {
+			element,
+			separator: String::from(","),
+			trim: true,
+			min_len: None,
+			max_len: None,
+			dedup: None,
+		}
+	}
+	/// Sets what the values are separated by
+	pub fn separator(mut self, separator: impl Into<String>) -> Self {
+		self.separator = separator.into();
+		self
+	}
+	/// Keeps the whitespace around each value
+	pub fn no_trim(mut self) -> Self {
+		self.trim = false;
+		self
+	}
+	/// Sets the smallest number of values allowed
+	pub fn min_len(mut self, min_len: usize) -> Self {
+		self.min_len = Some(min_len);
+		self
+	}
+	/// Sets the largest number of values allowed
+	pub fn max_len(mut self, max_len: usize) -> Self {
+		self.max_len = Some(max_len);
+		self
+	}
+	/// Removes values that are equal to an earlier value
+	pub fn dedup(mut self) -> Self where T::Output: PartialEq {
+		self.dedup = Some(PartialEq::eq);
+		self
+	}
+}
+
+impl<T: PromptHooks> PromptHooks for ListOf<T> {
+	type Output = Vec<T::Output>;
+	fn default_prompt(&self) -> String {
+		format!("Enter values separated by \"{}\": ", self.separator)
+	}
+	fn render_default(&self, default: &Self::Output) -> String {
+		let separator = if self.trim && !self.separator.trim().is_empty() {format!("{} ", self.separator)} else {self.separator.clone()};
+		default.iter().map(|value| self.element.render_default(value)).collect::<Vec<_>>().join(&separator)
+	}
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		let mut output = Vec::<T::Output>::new();
+		let mut position = 0;
+		for (i, value) in input.split(&*self.separator).enumerate() {
+			let value_position = position;
+			position += value.len() + self.separator.len();
+			let (value, value_position) = if self.trim {
+				let trimmed = value.trim_start();
+				(trimmed.trim_end(), value_position + value.len() - trimmed.len())
+			} else {
+				(value, value_position)
+			};
+			if self.trim && value.is_empty() {continue;}
+			let element = match self.element.parse_and_validate(value.to_string()) {
+				Ok(element) => element,
+				Err(error_message) => return Err(error_at(&input, value_position, &format!("Invalid value #{} ({value:?}): {error_message}", i + 1))),
+			};
+			if let Some(eq) = self.dedup && output.iter().any(|existing| eq(existing, &element)) {
+				continue;
+			}
+			output.push(element);
+		}
+		Ok(output)
+	}
+	fn validate(&self, output: &Self::Output) -> Result<(), String> {
+		if let Some(min_len) = self.min_len && output.len() < min_len {
+			return Err(format!("Invalid input, must have at least {min_len} {}", if min_len == 1 {"value"} else {"values"}));
+		}
+		if let Some(max_len) = self.max_len && output.len() > max_len {
+			return Err(format!("Invalid input, must have at most {max_len} {}", if max_len == 1 {"value"} else {"values"}));
+		}
+		Ok(())
+	}
+}
+
+impl<T: PromptHooks> TryRead for ListOf<T> {
+	type Output = Vec<T::Output>;
+	type Default = Vec<T::Output>;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		run_prompt_loop(term, &self, prompt, default)
+	}
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use basics::NonEmptyInput;
+	use testing::ScriptedTerminal;
+	
+	#[test]
+	fn values_are_split_and_trimmed() {
+		assert_eq!(ListOf::new(1..=10).parse(String::from(" 1, 2 ,,3 ")), Ok(vec!(1, 2, 3)));
+		assert_eq!(ListOf::new(NonEmptyInput).separator(";").no_trim().parse(String::from("a; b")), Ok(vec!(String::from("a"), String::from(" b"))));
+	}
+	
+	#[test]
+	fn errors_point_at_the_invalid_value() {
+		let error = ListOf::new(1..=10).parse(String::from("1,  20,3")).err().unwrap_or_default();
+		assert!(error.starts_with("Invalid value #2 (\"20\"): "), "{error}");
+		assert!(error.ends_with("\n1,  20,3\n    ^"), "{error}");
+		let error = ListOf::new(1..=10).separator(" - ").parse(String::from("1 - x"));
+		assert!(error.err().unwrap_or_default().ends_with("\n1 - x\n    ^"));
+	}
+	
+	#[test]
+	fn duplicates_are_removed() {
+		let tags = ListOf::new(NonEmptyInput).separator(" ").dedup().parse(String::from("a b  a c b"));
+		assert_eq!(tags, Ok(vec!(String::from("a"), String::from("b"), String::from("c"))));
+	}
+	
+	#[test]
+	fn length_is_checked() {
+		let script = ScriptedTerminal::new(&["80, 443, 8080", "80, 80, 443"]);
+		let output = ListOf::new(1..=65535).dedup().max_len(2).try_read_with(&mut script.terminal(), None, None);
+		assert_eq!(output.ok(), Some(vec!(80, 443)));
+		script.assert_shown("Invalid input, must have at most 2 values");
+	}
+}
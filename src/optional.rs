@@ -0,0 +1,83 @@
+use crate::*;
+use std::convert::Infallible;
+
+
+
+/// Allows an input to be skipped by entering nothing, in which case the output is `None`
+/// 
+/// Any other input goes through the inner type's parsing, validation, and retries. This works with every type that implements `PromptHooks`, which includes every built-in input other than lists
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// # use smart_read::prelude::*;
+/// let max_connections = prompt!("Max connections: "; Optional(1..=1000));  // shows "Max connections: (leave blank to skip) "
+/// if let Some(max_connections) = max_connections {
+/// 	println!("Limiting to {max_connections} connections");
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Optional<T>(pub T);
+
+impl<T: PromptHooks> TryRead for Optional<T> {
+	type Output = Option<T::Output>;
+	type Default = Infallible; // ensure no default can be given, since an empty input is used to skip
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, _default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		let prompt = prompt.unwrap_or_else(|| self.0.default_prompt()) + "(leave blank to skip) ";
+		let mut retries = RetryCounter::new(term);
+		// skipping acts as the default value, so it's also used for timeouts, non-interactive mode, and `OnRetriesExhausted::UseDefault`
+		let mut default = Some(None);
+		loop {
+			
+			let input = term.prompt_line(&prompt, true)?;
+			if input.is_empty() {
+				return Ok(None);
+			}
+			
+			let error_message = match self.0.parse_and_validate(input) {
+				Ok(output) => return Ok(Some(output)),
+				Err(error_message) => error_message,
+			};
+			if let Some(default) = retries.invalid_input(term, &error_message, &mut default)? {
+				return Ok(default);
+			}
+			
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use basics::UsizeInput;
+	use testing::ScriptedTerminal;
+	
+	#[test]
+	fn blank_inputs_are_skipped() {
+		let script = ScriptedTerminal::new(&[""]);
+		let output = Optional(1..=10).try_read_with(&mut script.terminal(), Some(String::from("n: ")), None);
+		assert!(matches!(output, Ok(None)), "{output:?}");
+		script.assert_shown("n: (leave blank to skip) \n");
+	}
+	
+	#[test]
+	fn inputs_are_validated_by_the_inner_type() {
+		let script = ScriptedTerminal::new(&["50", "5"]);
+		let output = Optional(1..=10).try_read_with(&mut script.terminal(), None, None);
+		assert!(matches!(output, Ok(Some(5))), "{output:?}");
+		script.assert_shown("Enter a number within the range [1, 10]: (leave blank to skip) 50\n");
+		script.assert_shown("Invalid input, not within bounds");
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn running_out_of_retries_can_skip() {
+		let script = ScriptedTerminal::new(&["abc", "5"]);
+		let output = Prompt::new("n: ").retry_policy(RetryPolicy::max_attempts(1).or_use_default()).try_read_with(&mut script.terminal(), Optional(UsizeInput));
+		assert!(matches!(output, Ok(None)), "{output:?}");
+		let output = Prompt::new("n: ").retries(1).try_read_with(&mut script.terminal(), Optional(UsizeInput));
+		assert!(matches!(output, Ok(Some(5))), "{output:?}");
+	}
+}
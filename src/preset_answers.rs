@@ -0,0 +1,454 @@
+use crate::*;
+use std::{collections::{HashMap, HashSet}, iter::Peekable, path::Path, rc::Rc, str::Chars};
+
+
+
+/// An answer that was given without the user typing it, such as from an environment variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetAnswer {
+	/// Where the answer came from, for example `SMART_READ_ANSWER_DEPLOY_TARGET`
+	pub source: String,
+	/// The answer itself
+	pub answer: String,
+}
+
+
+
+/// Gets the name of the environment variable that can give the answer for a prompt id
+/// 
+/// This is `SMART_READ_ANSWER_` followed by the prompt id in uppercase, with every character that isn't a letter or digit replaced by `_`. For example, the prompt id `"deploy-target"` gives `SMART_READ_ANSWER_DEPLOY_TARGET`
+pub fn env_var_name(prompt_id: &str) -> String {
+	let id = prompt_id.chars()
+		.map(|c| if c.is_ascii_alphanumeric() {c.to_ascii_uppercase()} else {'_'})
+		.collect::<String>();
+	format!("SMART_READ_ANSWER_{id}")
+}
+
+/// Gets the answer for a prompt id from the environment, if there is one (see `env_var_name()`)
+pub fn env_answer(prompt_id: &str) -> Option<PresetAnswer> {
+	let source = env_var_name(prompt_id);
+	let answer = std::env::var(&source).ok()?;
+	Some(PresetAnswer {source, answer})
+}
+
+
+
+/// A set of answers keyed by prompt id, usually loaded from a TOML or JSON file
+/// 
+/// When this is set as a terminal's `answer_file`, every prompt with a `prompt_id` is answered from it instead of reading input. Answers go through the same parsing and validation as typed input, and an invalid answer makes the read return `SmartReadError::InvalidAnswer`
+/// 
+/// Prompts that have no answer in the file return `SmartReadError::MissingAnswer`, unless `with_fallback()` is used, in which case they read input like normal. Answers from environment variables (see `env_var_name()`) are still used over answers from the file, and the file's answer then counts as used
+/// 
+/// Clones of an `AnswerFile` share the same answers, so a clone can be given to a terminal and the original can be checked once everything is done:
+/// 
+/// ```ignore
+/// let answers = AnswerFile::load("answers.toml")?;
+/// let mut term = Terminal::stdio();
+/// term.answer_file = Some(answers.clone());
+/// set_default_terminal(term);
+/// 
+/// let target = prompt!("Deploy to: "; prompt_id = "deploy_target"; = "staging", "production");
+/// let port = prompt!("Port: "; prompt_id = "port"; 1..=65535);
+/// 
+/// answers.finish()?; // returns an error if the file has answers that were never used, or prompts that had no answer
+/// ```
+/// 
+/// Supported TOML: `key = value` lines (with bare, quoted, or dotted keys), `[table]` headers (which prefix the following keys with `table.`), comments, and string, number, and bool values
+/// 
+/// Supported JSON: an object whose values are strings, numbers, bools, or objects (nested objects are flattened into `outer.inner` keys)
+#[derive(Clone)]
+pub struct AnswerFile {
+	state: Rc<RefCell<AnswerFileState>>,
+}
+
+struct AnswerFileState {
+	source: String,
+	answers: HashMap<String, String>,
+	used_keys: HashSet<String>,
+	missing_ids: Vec<String>,
+	allow_fallback: bool,
+}
+
+impl AnswerFile {
+	/// Loads answers from a file, the format is chosen by the file extension (`.json` for JSON, anything else for TOML)
+	pub fn load(path: impl AsRef<Path>) -> ReadResult<Self> {
+		let path = path.as_ref();
+		let text = std::fs::read_to_string(path)?;
+		let source = path.display().to_string();
+		if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")) {
+			Self::from_json(&text, source)
+		} else {
+			Self::from_toml(&text, source)
+		}
+	}
+	/// Parses answers from TOML text, `source` is used for error messages (usually the file path)
+	pub fn from_toml(text: &str, source: impl Into<String>) -> ReadResult<Self> {
+		let source = source.into();
+		let answers = parse_toml(text).map_err(|err| SmartReadError::InvalidAnswerFile(format!("{source}: {err}")))?;
+		Ok(Self::new(answers, source))
+	}
+	/// Parses answers from JSON text, `source` is used for error messages (usually the file path)
+	pub fn from_json(text: &str, source: impl Into<String>) -> ReadResult<Self> {
+		let source = source.into();
+		let answers = parse_json(text).map_err(|err| SmartReadError::InvalidAnswerFile(format!("{source}: {err}")))?;
+		Ok(Self::new(answers, source))
+	}
+	/// Initializer from already-parsed answers
+	pub fn new(answers: HashMap<String, String>, source: impl Into<String>) -> Self {
+		Self {
+			state: Rc::new(RefCell::new(AnswerFileState {
+				source: source.into(),
+				answers,
+				used_keys: HashSet::new(),
+				missing_ids: vec!(),
+				allow_fallback: false,
+			})),
+		}
+	}
+	/// Makes prompts that don't have an answer read input like normal, instead of returning `SmartReadError::MissingAnswer`
+	pub fn with_fallback(self) -> Self {
+		self.state.borrow_mut().allow_fallback = true;
+		self
+	}
+	/// Returns whether prompts that don't have an answer should read input like normal
+	pub fn allows_fallback(&self) -> bool {
+		self.state.borrow().allow_fallback
+	}
+	/// Gets the answer for a prompt id and marks it as used, or records the id as missing if there is no answer for it
+	pub fn answer(&self, prompt_id: &str) -> Option<PresetAnswer> {
+		let mut state = self.state.borrow_mut();
+		let Some(answer) = state.answers.get(prompt_id).cloned() else {
+			if !state.missing_ids.iter().any(|id| id == prompt_id) {
+				state.missing_ids.push(prompt_id.to_string());
+			}
+			return None;
+		};
+		state.used_keys.insert(prompt_id.to_string());
+		Some(PresetAnswer {source: format!("{} (key \"{prompt_id}\")", state.source), answer})
+	}
+	// marks the answer for a prompt id as used when an environment variable is used instead, so `finish()` doesn't report it as misspelled
+	pub(crate) fn mark_overridden(&self, prompt_id: &str) {
+		let mut state = self.state.borrow_mut();
+		if state.answers.contains_key(prompt_id) {
+			state.used_keys.insert(prompt_id.to_string());
+		}
+	}
+	/// Returns the keys that have not been used by any prompt, in alphabetical order
+	pub fn unused_keys(&self) -> Vec<String> {
+		let state = self.state.borrow();
+		let mut output = state.answers.keys().filter(|key| !state.used_keys.contains(*key)).cloned().collect::<Vec<_>>();
+		output.sort();
+		output
+	}
+	/// Returns the prompt ids that were asked for but didn't have an answer, in the order they were asked for
+	pub fn missing_ids(&self) -> Vec<String> {
+		self.state.borrow().missing_ids.clone()
+	}
+	/// Call this once all prompts are done, it returns `SmartReadError::InvalidAnswerFile` if any answers were never used or any prompt ids had no answer (which usually means a key is misspelled)
+	pub fn finish(&self) -> ReadResult<()> {
+		let mut problems = vec!();
+		let unused_keys = self.unused_keys();
+		if !unused_keys.is_empty() {
+			problems.push(format!("these answers were never used: {}", unused_keys.join(", ")));
+		}
+		let missing_ids = self.missing_ids();
+		if !missing_ids.is_empty() {
+			problems.push(format!("these prompts had no answer: {}", missing_ids.join(", ")));
+		}
+		if problems.is_empty() {return Ok(());}
+		Err(SmartReadError::InvalidAnswerFile(format!("{}: {}", self.state.borrow().source, problems.join("; "))))
+	}
+}
+
+
+
+fn parse_toml(text: &str) -> Result<HashMap<String, String>, String> {
+	let mut output = HashMap::new();
+	let mut table_prefix = String::new();
+	for (i, line) in text.lines().enumerate() {
+		let line_num = i + 1;
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {continue;}
+		
+		// table header
+		if let Some(table) = line.strip_prefix('[') {
+			let mut chars = table.chars().peekable();
+			let table = parse_toml_key(&mut chars, ']').map_err(|err| format!("line {line_num}: {err}"))?;
+			check_toml_line_end(&chars.collect::<String>(), line_num)?;
+			table_prefix = table + ".";
+			continue;
+		}
+		
+		// key
+		let mut chars = line.chars().peekable();
+		let key = parse_toml_key(&mut chars, '=').map_err(|err| format!("line {line_num}: {err}"))?;
+		let rest = chars.collect::<String>();
+		let rest = rest.trim_start();
+		
+		// value
+		let mut chars = rest.chars().peekable();
+		let value = match chars.peek() {
+			Some('"') => {chars.next(); parse_quoted_string(&mut chars, '"').map_err(|err| format!("line {line_num}: {err}"))?}
+			Some('\'') => {chars.next(); parse_quoted_string(&mut chars, '\'').map_err(|err| format!("line {line_num}: {err}"))?}
+			Some('[' | '{') => return Err(format!("line {line_num}: arrays and inline tables are not supported")),
+			Some(_) => {
+				let value = chars.by_ref().take_while(|c| !c.is_whitespace() && *c != '#').collect::<String>();
+				check_bare_value(&value).map_err(|err| format!("line {line_num}: {err}"))?;
+				value
+			}
+			None => return Err(format!("line {line_num}: missing value")),
+		};
+		check_toml_line_end(&chars.collect::<String>(), line_num)?;
+		
+		let key = table_prefix.clone() + &key;
+		if output.insert(key.clone(), value).is_some() {
+			return Err(format!("line {line_num}: duplicate key \"{key}\""));
+		}
+	}
+	Ok(output)
+}
+
+// parses a key like `server."example.com".port` up to and including `end`, only bare parts are split on dots and trimmed
+fn parse_toml_key(chars: &mut Peekable<Chars>, end: char) -> Result<String, String> {
+	let mut key = String::new();
+	loop {
+		skip_whitespace(chars);
+		match chars.peek() {
+			Some('"') => {chars.next(); key += &parse_quoted_string(chars, '"')?;}
+			Some('\'') => {chars.next(); key += &parse_quoted_string(chars, '\'')?;}
+			_ => {
+				let part = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))).collect::<String>();
+				if part.is_empty() {return Err(String::from("missing key"));}
+				key += &part;
+			}
+		}
+		skip_whitespace(chars);
+		match chars.next() {
+			Some('.') => key.push('.'),
+			Some(c) if c == end => return Ok(key),
+			Some(c) => return Err(format!("unexpected '{c}' in key")),
+			None => return Err(format!("missing '{end}'")),
+		}
+	}
+}
+
+fn check_toml_line_end(rest: &str, line_num: usize) -> Result<(), String> {
+	let rest = rest.trim();
+	if rest.is_empty() || rest.starts_with('#') {
+		Ok(())
+	} else {
+		Err(format!("line {line_num}: unexpected text \"{rest}\""))
+	}
+}
+
+
+
+fn parse_json(text: &str) -> Result<HashMap<String, String>, String> {
+	let mut chars = text.chars().peekable();
+	let mut output = HashMap::new();
+	skip_whitespace(&mut chars);
+	if chars.next() != Some('{') {return Err(String::from("expected an object"));}
+	parse_json_object(&mut chars, "", &mut output)?;
+	skip_whitespace(&mut chars);
+	if let Some(c) = chars.next() {return Err(format!("unexpected '{c}' after the object"));}
+	Ok(output)
+}
+
+// the opening '{' should already be consumed
+fn parse_json_object(chars: &mut Peekable<Chars>, prefix: &str, output: &mut HashMap<String, String>) -> Result<(), String> {
+	skip_whitespace(chars);
+	if chars.peek() == Some(&'}') {
+		chars.next();
+		return Ok(());
+	}
+	loop {
+		skip_whitespace(chars);
+		if chars.next() != Some('"') {return Err(String::from("expected a key"));}
+		let key = prefix.to_string() + &parse_quoted_string(chars, '"')?;
+		skip_whitespace(chars);
+		if chars.next() != Some(':') {return Err(format!("expected ':' after \"{key}\""));}
+		skip_whitespace(chars);
+		match chars.next() {
+			Some('{') => parse_json_object(chars, &format!("{key}."), output)?,
+			Some('[') => return Err(format!("\"{key}\": arrays are not supported")),
+			Some(first_char) => {
+				let value = if first_char == '"' {
+					parse_quoted_string(chars, '"')?
+				} else {
+					let mut value = String::from(first_char);
+					while let Some(c) = chars.peek() && (c.is_alphanumeric() || matches!(c, '-' | '+' | '.')) {
+						value.push(*c);
+						chars.next();
+					}
+					if value == "null" {return Err(format!("\"{key}\": null is not supported"));}
+					check_bare_value(&value).map_err(|err| format!("\"{key}\": {err}"))?;
+					value
+				};
+				if output.insert(key.clone(), value).is_some() {
+					return Err(format!("duplicate key \"{key}\""));
+				}
+			}
+			None => return Err(String::from("unexpected end of text")),
+		}
+		skip_whitespace(chars);
+		match chars.next() {
+			Some(',') => continue,
+			Some('}') => return Ok(()),
+			_ => return Err(format!("expected ',' or '}}' after \"{key}\"")),
+		}
+	}
+}
+
+
+
+// values without quotes have to be numbers or bools
+fn check_bare_value(value: &str) -> Result<(), String> {
+	let is_number = value.chars().any(|c| c.is_ascii_digit()) && value.replace('_', "").parse::<f64>().is_ok();
+	if is_number || value == "true" || value == "false" {
+		Ok(())
+	} else {
+		Err(format!("invalid value \"{value}\", text has to be in quotes"))
+	}
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+	while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+// the opening quote should already be consumed, escapes are only handled for double quotes
+fn parse_quoted_string(chars: &mut impl Iterator<Item = char>, quote: char) -> Result<String, String> {
+	let mut output = String::new();
+	while let Some(c) = chars.next() {
+		match c {
+			_ if c == quote => return Ok(output),
+			'\\' if quote == '"' => match chars.next() {
+				Some('n') => output.push('\n'),
+				Some('t') => output.push('\t'),
+				Some('r') => output.push('\r'),
+				Some('"') => output.push('"'),
+				Some('\\') => output.push('\\'),
+				Some('/') => output.push('/'),
+				Some('u') => {
+					let hex = chars.by_ref().take(4).collect::<String>();
+					let escaped_char = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).ok_or_else(|| format!("invalid escape \"\\u{hex}\""))?;
+					output.push(escaped_char);
+				}
+				Some(c) => return Err(format!("invalid escape \"\\{c}\"")),
+				None => break,
+			},
+			_ => output.push(c),
+		}
+	}
+	Err(String::from("missing closing quote"))
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn answers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+		pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+	}
+	
+	#[test]
+	fn toml_is_parsed() {
+		let text = r#"
+			# comment
+			name = "a \"b\" \u00e9\n"  # trailing comment
+			port = 8080
+			ratio = -1.5
+			big = 1_000
+			enabled = true
+			path = 'C:\dir'
+			
+			"a.b" = "quoted"
+			" c " = "spaces"
+			site."example.com" = 'dotted'
+			
+			[server . "db"]
+			host = "localhost"
+			
+			[ "my.table" ]
+			x = 1
+		"#;
+		let expected = answers(&[
+			("name", "a \"b\" \u{e9}\n"),
+			("port", "8080"),
+			("ratio", "-1.5"),
+			("big", "1_000"),
+			("enabled", "true"),
+			("path", "C:\\dir"),
+			("a.b", "quoted"),
+			(" c ", "spaces"),
+			("site.example.com", "dotted"),
+			("server.db.host", "localhost"),
+			("my.table.x", "1"),
+		]);
+		assert_eq!(parse_toml(text), Ok(expected));
+	}
+	
+	#[test]
+	fn invalid_toml_is_rejected() {
+		assert_eq!(parse_toml("a = 1\na = 2"), Err(String::from("line 2: duplicate key \"a\"")));
+		assert_eq!(parse_toml("a = @"), Err(String::from("line 1: invalid value \"@\", text has to be in quotes")));
+		assert_eq!(parse_toml("a = hello"), Err(String::from("line 1: invalid value \"hello\", text has to be in quotes")));
+		assert_eq!(parse_toml("a = \"\\q\""), Err(String::from("line 1: invalid escape \"\\q\"")));
+		assert_eq!(parse_toml("a = \"open"), Err(String::from("line 1: missing closing quote")));
+		assert_eq!(parse_toml("a = [1, 2]"), Err(String::from("line 1: arrays and inline tables are not supported")));
+		assert_eq!(parse_toml("[table"), Err(String::from("line 1: missing ']'")));
+		assert_eq!(parse_toml("a b = 1"), Err(String::from("line 1: unexpected 'b' in key")));
+		assert_eq!(parse_toml("= 1"), Err(String::from("line 1: missing key")));
+		assert_eq!(parse_toml("a"), Err(String::from("line 1: missing '='")));
+		assert_eq!(parse_toml("a ="), Err(String::from("line 1: missing value")));
+		assert_eq!(parse_toml("a = 1 2"), Err(String::from("line 1: unexpected text \"2\"")));
+	}
+	
+	#[test]
+	fn json_is_parsed() {
+		let text = r#" {"name": "a \"b\" \u00e9\n", "port": 8080, "ratio": -1.5e3, "enabled": false, "server": {"db": {"host": "localhost"}}, "empty": {}} "#;
+		let expected = answers(&[
+			("name", "a \"b\" \u{e9}\n"),
+			("port", "8080"),
+			("ratio", "-1.5e3"),
+			("enabled", "false"),
+			("server.db.host", "localhost"),
+		]);
+		assert_eq!(parse_json(text), Ok(expected));
+	}
+	
+	#[test]
+	fn invalid_json_is_rejected() {
+		assert_eq!(parse_json(r#"{"a": 1, "a": 2}"#), Err(String::from("duplicate key \"a\"")));
+		assert_eq!(parse_json(r#"{"a": null}"#), Err(String::from("\"a\": null is not supported")));
+		assert_eq!(parse_json(r#"{"a": @}"#), Err(String::from("\"a\": invalid value \"@\", text has to be in quotes")));
+		assert_eq!(parse_json(r#"{"a": [1]}"#), Err(String::from("\"a\": arrays are not supported")));
+		assert_eq!(parse_json(r#"{"a": "\x"}"#), Err(String::from("invalid escape \"\\x\"")));
+		assert_eq!(parse_json(r#"{"a": 1} 2"#), Err(String::from("unexpected '2' after the object")));
+		assert_eq!(parse_json(r#"{"a": 1"#), Err(String::from("expected ',' or '}' after \"a\"")));
+		assert_eq!(parse_json("[]"), Err(String::from("expected an object")));
+	}
+	
+	#[test]
+	fn overridden_answers_count_as_used() {
+		let answer_file = AnswerFile::new(answers(&[("host", "a"), ("port", "1")]), "answers.toml");
+		answer_file.mark_overridden("host");
+		answer_file.mark_overridden("not_in_file");
+		assert_eq!(answer_file.unused_keys(), ["port"]);
+		assert!(answer_file.answer("port").is_some());
+		assert!(answer_file.finish().is_ok());
+	}
+	
+	#[test]
+	fn finish_reports_unused_and_missing_answers() {
+		let answer_file = AnswerFile::new(answers(&[("host", "a"), ("prot", "1")]), "answers.toml").with_fallback();
+		assert!(answer_file.answer("host").is_some());
+		assert!(answer_file.answer("port").is_none());
+		assert!(answer_file.answer("port").is_none());
+		assert_eq!(answer_file.missing_ids(), ["port"]);
+		let output = answer_file.finish();
+		assert!(matches!(&output, Err(SmartReadError::InvalidAnswerFile(reason)) if reason == "answers.toml: these answers were never used: prot; these prompts had no answer: port"), "{output:?}");
+	}
+}
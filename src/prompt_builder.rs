@@ -0,0 +1,215 @@
+use crate::*;
+use completion::Completer;
+use std::rc::Rc;
+
+
+
+/// A builder for a single read, this is what the `read!()` and `prompt!()` macros use internally
+/// 
+/// ```ignore
+/// let port = Prompt::new("Port: ").default(8080).help("The port that the server listens on").retries(3).id("port").read(1..=65535);
+/// // is the same as:
+/// println!("The port that the server listens on");
+/// let port = prompt!("Port: "; retry_policy = RetryPolicy::max_attempts(3); prompt_id = "port"; [8080] 1..=65535);
+/// ```
+/// 
+/// Every option only applies to this read, the terminal's own settings are restored afterwards
+#[must_use]
+pub struct Prompt<D = NoDefault> {
+	message: Option<String>,
+	default: D,
+	help: Option<String>,
+	retry_policy: Option<RetryPolicy>,
+	timeout: Option<Duration>,
+	non_interactive: Option<bool>,
+	prompt_id: Option<String>,
+	answer_file: Option<AnswerFile>,
+	recorder: Option<SessionRecorder>,
+	replay: Option<SessionReplay>,
+	completer: Option<Rc<dyn Completer>>,
+}
+
+impl Prompt {
+	/// Creates a prompt that shows the given message
+	pub fn new(message: impl Into<String>) -> Self {
+		Self {
+			message: Some(message.into()),
+			..Self::empty()
+		}
+	}
+	/// Creates a prompt without a message, so the input type's own prompt is used (this is what `read!()` uses)
+	pub fn empty() -> Self {
+		Self {
+			message: None,
+			default: NoDefault,
+			help: None,
+			retry_policy: None,
+			timeout: None,
+			non_interactive: None,
+			prompt_id: None,
+			answer_file: None,
+			recorder: None,
+			replay: None,
+			completer: None,
+		}
+	}
+	/// Sets the default value, which is used when nothing is entered (for list constraints, this is the index of the default option)
+	pub fn default<T>(self, default: T) -> Prompt<WithDefault<T>> {
+		Prompt {
+			message: self.message,
+			default: WithDefault(default),
+			help: self.help,
+			retry_policy: self.retry_policy,
+			timeout: self.timeout,
+			non_interactive: self.non_interactive,
+			prompt_id: self.prompt_id,
+			answer_file: self.answer_file,
+			recorder: self.recorder,
+			replay: self.replay,
+			completer: self.completer,
+		}
+	}
+}
+
+impl<D> Prompt<D> {
+	/// Sets text that is shown on its own line before the prompt
+	pub fn help(mut self, help: impl Into<String>) -> Self {
+		self.help = Some(help.into());
+		self
+	}
+	/// Gives up after the given number of invalid inputs, same as `.retry_policy(RetryPolicy::max_attempts(max_attempts))`
+	pub fn retries(self, max_attempts: usize) -> Self {
+		self.retry_policy(RetryPolicy::max_attempts(max_attempts))
+	}
+	/// Sets what to do when an invalid input is given (see `Terminal::retry_policy`)
+	pub fn retry_policy(mut self, retry_policy: impl Into<RetryPolicy>) -> Self {
+		self.retry_policy = Some(retry_policy.into());
+		self
+	}
+	/// Sets how long to wait for input before using the default value (see `Terminal::timeout`)
+	pub fn timeout(mut self, timeout: impl Into<Duration>) -> Self {
+		self.timeout = Some(timeout.into());
+		self
+	}
+	/// Sets whether the default value should be used instead of asking for input (see `Terminal::non_interactive`)
+	pub fn non_interactive(mut self, non_interactive: impl Into<bool>) -> Self {
+		self.non_interactive = Some(non_interactive.into());
+		self
+	}
+	/// Gives the prompt a stable name, which lets its answer be given ahead of time (see `Terminal::prompt_id`)
+	pub fn id(mut self, id: impl Into<String>) -> Self {
+		self.prompt_id = Some(id.into());
+		self
+	}
+	/// Same as `id()`, this is what the macros' `prompt_id = ...;` option uses
+	pub fn prompt_id(self, id: impl Into<String>) -> Self {
+		self.id(id)
+	}
+	/// Sets the answers to use for prompts with ids (see `Terminal::answer_file`)
+	pub fn answer_file(mut self, answer_file: impl Into<AnswerFile>) -> Self {
+		self.answer_file = Some(answer_file.into());
+		self
+	}
+	/// Records this read (see `Terminal::recorder`)
+	pub fn recorder(mut self, recorder: impl Into<SessionRecorder>) -> Self {
+		self.recorder = Some(recorder.into());
+		self
+	}
+	/// Takes this read's input from a recorded session (see `Terminal::replay`)
+	pub fn replay(mut self, replay: impl Into<SessionReplay>) -> Self {
+		self.replay = Some(replay.into());
+		self
+	}
+	/// Suggests completions when Tab is pressed (see `Terminal::completer`)
+	pub fn completer(mut self, completer: impl Completer + 'static) -> Self {
+		self.completer = Some(Rc::new(completer));
+		self
+	}
+	
+	/// Reads the given input type using the default terminal (see `set_default_terminal()`), and panics if the read fails, same as `read!()` and `prompt!()`
+	pub fn read<R>(self, input: R) -> R::Output
+	where
+		R: TryRead,
+		D: IntoDefault<R::Default>,
+	{
+		#[allow(clippy::unwrap_used)] // REASON: this is the same as `read!()`, `try_read()` should be used to handle errors
+		self.try_read(input).unwrap()
+	}
+	/// Reads the given input type using the default terminal (see `set_default_terminal()`), same as `try_read!()` and `try_prompt!()`
+	pub fn try_read<R>(self, input: R) -> ReadResult<R::Output>
+	where
+		R: TryRead,
+		D: IntoDefault<R::Default>,
+	{
+		with_default_terminal(|term| self.try_read_with(term, input))?
+	}
+	/// Reads the given input type using the given terminal
+	pub fn try_read_with<R>(self, term: &mut Terminal, input: R) -> ReadResult<R::Output>
+	where
+		R: TryRead,
+		D: IntoDefault<R::Default>,
+	{
+		let prev_retry_policy = set_field(&mut term.retry_policy, self.retry_policy);
+		let prev_timeout = set_field(&mut term.timeout, self.timeout);
+		let prev_non_interactive = set_field(&mut term.non_interactive, self.non_interactive);
+		let prev_prompt_id = set_field(&mut term.prompt_id, self.prompt_id);
+		let prev_answer_file = set_field(&mut term.answer_file, self.answer_file);
+		let prev_recorder = set_field(&mut term.recorder, self.recorder);
+		let prev_replay = set_field(&mut term.replay, self.replay);
+		let prev_completer = set_field(&mut term.completer, self.completer);
+		
+		let output = (|| {
+			if let Some(help) = self.help {
+				term.println(&help)?;
+			}
+			input.try_read_with(term, self.message, self.default.into_default())
+		})();
+		
+		restore_field(&mut term.retry_policy, prev_retry_policy);
+		restore_field(&mut term.timeout, prev_timeout);
+		restore_field(&mut term.non_interactive, prev_non_interactive);
+		restore_field(&mut term.prompt_id, prev_prompt_id);
+		restore_field(&mut term.answer_file, prev_answer_file);
+		restore_field(&mut term.recorder, prev_recorder);
+		restore_field(&mut term.replay, prev_replay);
+		restore_field(&mut term.completer, prev_completer);
+		output
+	}
+}
+
+// returns the field's previous value if it was changed
+fn set_field<T>(field: &mut Option<T>, value: Option<T>) -> Option<Option<T>> {
+	value.map(|value| field.replace(value))
+}
+
+fn restore_field<T>(field: &mut Option<T>, prev_value: Option<Option<T>>) {
+	if let Some(prev_value) = prev_value {
+		*field = prev_value;
+	}
+}
+
+
+
+/// Used by `Prompt` when no default value is given
+pub struct NoDefault;
+
+/// Used by `Prompt` to hold a default value that hasn't been converted to the input type's default type yet
+pub struct WithDefault<T>(pub T);
+
+/// Converts a `Prompt`'s default value to the default type of the input being read
+pub trait IntoDefault<T> {
+	/// Does the conversion
+	fn into_default(self) -> Option<T>;
+}
+
+impl<T> IntoDefault<T> for NoDefault {
+	fn into_default(self) -> Option<T> {
+		None
+	}
+}
+
+impl<T, D: Into<T>> IntoDefault<T> for WithDefault<D> {
+	fn into_default(self) -> Option<T> {
+		Some(self.0.into())
+	}
+}
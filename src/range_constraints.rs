@@ -1,106 +1,90 @@
 use crate::*;
-use std::{ops::{Range, RangeBounds, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive}, str::FromStr};
+use std::{marker::PhantomData, ops::{Range, RangeBounds, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive}, str::FromStr};
 
 
 
 /// Internal utility function
-pub fn read_range<T, R>(range: R, mut prompt: String, default: Option<T>) -> BoxResult<T>
+pub fn read_range<T, R>(term: &mut Terminal, range: R, prompt: String, default: Option<T>) -> ReadResult<T>
 where
 	T: Display + FromStr + PartialOrd<T>,
 	R: RangeBounds<T>,
 	<T as FromStr>::Err: Display,
 {
-	if let Some(default) = default.as_ref() {
-		prompt += &format!("(default: {default}) ");
-	}
-	loop {
-		
-		print!("{prompt}");
-		let output_string = read_stdin()?;
-		if output_string.is_empty() && let Some(default) = default {
-			return Ok(default);
-		}
-		
-		let output = match output_string.parse::<T>() {
-			Ok(v) => v,
-			Err(err) => {
-				println!();
-				println!("Could not parse input ({err})");
-				continue;
-			}
-		};
-		if range.contains(&output) {
-			return Ok(output);
-		}
-		
-		println!();
-		println!("Invalid input, not within bounds");
-	}
+	run_prompt_loop(term, &RangeHooks {range, _output: PhantomData}, Some(prompt), default)
 }
 
-
-
-impl<T> TryRead for Range<T>
-where
-	T: Display + FromStr + PartialOrd<T>,
-	<T as FromStr>::Err: Display,
-{
-	type Output = T;
-	type Default = T;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Output>) -> BoxResult<Self::Output> {
-		let prompt = prompt.unwrap_or_else(|| format!("Enter a number within the range [{:.1}, {:.1}): ", self.start, self.end));
-		read_range(self, prompt, default)
-	}
+struct RangeHooks<T, R> {
+	range: R,
+	_output: PhantomData<T>,
 }
 
-impl<T> TryRead for RangeInclusive<T>
+impl<T, R> DisplayPromptHooks for RangeHooks<T, R>
 where
 	T: Display + FromStr + PartialOrd<T>,
+	R: RangeBounds<T>,
 	<T as FromStr>::Err: Display,
 {
 	type Output = T;
-	type Default = T;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Output>) -> BoxResult<Self::Output> {
-		let prompt = prompt.unwrap_or_else(|| format!("Enter a number within the range [{:.1}, {:.1}]: ", self.start(), self.end()));
-		read_range(self, prompt, default)
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		parse_number(input)
+	}
+	fn validate(&self, output: &Self::Output) -> Result<(), String> {
+		check_bounds(&self.range, output)
 	}
 }
 
-impl<T> TryRead for RangeTo<T>
+fn parse_number<T>(input: String) -> Result<T, String>
 where
-	T: Display + FromStr + PartialOrd<T>,
+	T: FromStr,
 	<T as FromStr>::Err: Display,
 {
-	type Output = T;
-	type Default = T;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Output>) -> BoxResult<Self::Output> {
-		let prompt = prompt.unwrap_or_else(|| format!("Enter a number which is less than {:.1}: ", self.end));
-		read_range(self, prompt, default)
-	}
+	input.parse::<T>().map_err(|err| format!("Could not parse input (error: {err})"))
 }
 
-impl<T> TryRead for RangeFrom<T>
-where
-	T: Display + FromStr + PartialOrd<T>,
-	<T as FromStr>::Err: Display,
-{
-	type Output = T;
-	type Default = T;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Output>) -> BoxResult<Self::Output> {
-		let prompt = prompt.unwrap_or_else(|| format!("Enter a number which is at least {:.1}", self.start));
-		read_range(self, prompt, default)
+fn check_bounds<T: PartialOrd<T>>(range: &impl RangeBounds<T>, output: &T) -> Result<(), String> {
+	if !range.contains(output) {
+		return Err(String::from("Invalid input, not within bounds"));
 	}
+	Ok(())
 }
 
-impl<T> TryRead for RangeToInclusive<T>
-where
-	T: Display + FromStr + PartialOrd<T>,
-	<T as FromStr>::Err: Display,
-{
-	type Output = T;
-	type Default = T;
-	fn try_read_line(self, prompt: Option<String>, default: Option<Self::Output>) -> BoxResult<Self::Output> {
-		let prompt = prompt.unwrap_or_else(|| format!("Enter a number which is at most {:.1}: ", self.end));
-		read_range(self, prompt, default)
-	}
+
+
+macro_rules! implement_range_input {
+	($range_type:ident, $range:ident => $default_prompt:expr) => {
+		impl<T> DisplayPromptHooks for $range_type<T>
+		where
+			T: Display + FromStr + PartialOrd<T>,
+			<T as FromStr>::Err: Display,
+		{
+			type Output = T;
+			fn default_prompt(&self) -> String {
+				let $range = self;
+				$default_prompt
+			}
+			fn parse(&self, input: String) -> Result<Self::Output, String> {
+				parse_number(input)
+			}
+			fn validate(&self, output: &Self::Output) -> Result<(), String> {
+				check_bounds(self, output)
+			}
+		}
+		impl<T> TryRead for $range_type<T>
+		where
+			T: Display + FromStr + PartialOrd<T>,
+			<T as FromStr>::Err: Display,
+		{
+			type Output = T;
+			type Default = T;
+			fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Output>) -> ReadResult<Self::Output> {
+				run_prompt_loop(term, &self, prompt, default)
+			}
+		}
+	};
 }
+
+implement_range_input!(Range, range => format!("Enter a number within the range [{:.1}, {:.1}): ", range.start, range.end));
+implement_range_input!(RangeInclusive, range => format!("Enter a number within the range [{:.1}, {:.1}]: ", range.start(), range.end()));
+implement_range_input!(RangeTo, range => format!("Enter a number which is less than {:.1}: ", range.end));
+implement_range_input!(RangeFrom, range => format!("Enter a number which is at least {:.1}: ", range.start));
+implement_range_input!(RangeToInclusive, range => format!("Enter a number which is at most {:.1}: ", range.end));
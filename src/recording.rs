@@ -0,0 +1,279 @@
+use crate::*;
+use std::{path::Path, rc::Rc};
+
+
+
+/// One step of an interactive session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEvent {
+	/// A prompt was shown to the user
+	Prompt(String),
+	/// A line of input was given
+	Input(String),
+	/// The default value was used, either because of an empty input, a timeout, or non-interactive mode
+	DefaultUsed,
+	/// A fuzzy-search suggestion was accepted, this holds the suggested string
+	SuggestionAccepted(String),
+	/// A secret was given (see `SecretInput`), its content is never recorded
+	SecretInput,
+}
+
+impl SessionEvent {
+	/// Converts the event to a single line of text, this is the format used by `SessionRecorder::save()`
+	pub fn to_line(&self) -> String {
+		match self {
+			Self::Prompt(prompt) => format!("prompt: {}", escape(prompt)),
+			Self::Input(input) => format!("input: {}", escape(input)),
+			Self::DefaultUsed => String::from("default"),
+			Self::SecretInput => String::from("secret"),
+			Self::SuggestionAccepted(suggestion) => format!("suggestion: {}", escape(suggestion)),
+		}
+	}
+	/// Parses a line created by `to_line()`
+	pub fn from_line(line: &str) -> Option<Self> {
+		if line == "default" {return Some(Self::DefaultUsed);}
+		if line == "secret" {return Some(Self::SecretInput);}
+		let (kind, text) = line.split_once(": ")?;
+		let text = unescape(text);
+		match kind {
+			"prompt" => Some(Self::Prompt(text)),
+			"input" => Some(Self::Input(text)),
+			"suggestion" => Some(Self::SuggestionAccepted(text)),
+			_ => None,
+		}
+	}
+}
+
+impl Display for SessionEvent {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Prompt(prompt) => write!(f, "prompt {prompt:?}"),
+			Self::Input(input) => write!(f, "input {input:?}"),
+			Self::DefaultUsed => write!(f, "default value used"),
+			Self::SecretInput => write!(f, "secret input"),
+			Self::SuggestionAccepted(suggestion) => write!(f, "suggestion {suggestion:?} accepted"),
+		}
+	}
+}
+
+pub(crate) fn escape(text: &str) -> String {
+	text.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r").replace('\t', "\\t")
+}
+
+pub(crate) fn unescape(text: &str) -> String {
+	let mut output = String::new();
+	let mut chars = text.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			output.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('n') => output.push('\n'),
+			Some('r') => output.push('\r'),
+			Some('t') => output.push('\t'),
+			Some(c) => output.push(c),
+			None => output.push('\\'),
+		}
+	}
+	output
+}
+
+
+
+/// Records every prompt, input, default value, and accepted suggestion of a terminal (see `Terminal::recorder`)
+/// 
+/// Secrets (see `SecretInput`) are never recorded, only the fact that one was given
+/// 
+/// Clones of a `SessionRecorder` share the same events, so a clone can be given to a terminal and the original can be saved once everything is done:
+/// 
+/// ```ignore
+/// let recorder = SessionRecorder::new();
+/// let mut term = Terminal::stdio();
+/// term.recorder = Some(recorder.clone());
+/// set_default_terminal(term);
+/// 
+/// run_program();
+/// recorder.save("session.txt")?;
+/// ```
+#[derive(Clone, Default)]
+pub struct SessionRecorder {
+	events: Rc<RefCell<Vec<SessionEvent>>>,
+}
+
+impl SessionRecorder {
+	/// Basic initializer
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Adds an event to the recording
+	pub fn record(&self, event: SessionEvent) {
+		self.events.borrow_mut().push(event);
+	}
+	/// Returns all the events recorded so far
+	pub fn events(&self) -> Vec<SessionEvent> {
+		self.events.borrow().clone()
+	}
+	/// Converts the recording to text, with one event per line
+	pub fn to_text(&self) -> String {
+		self.events.borrow().iter().map(|event| event.to_line() + "\n").collect()
+	}
+	/// Writes the recording to a file, which can be loaded with `SessionReplay::load()`
+	pub fn save(&self, path: impl AsRef<Path>) -> ReadResult<()> {
+		std::fs::write(path, self.to_text())?;
+		Ok(())
+	}
+}
+
+
+
+/// Replays a recorded session (see `Terminal::replay`)
+/// 
+/// While replaying, every prompt must match the recording exactly, and inputs are taken from the recording instead of being read. If the program asks for something different than what was recorded, the read returns `SmartReadError::ReplayDiverged`, which says which step of the recording was expected
+#[derive(Clone)]
+pub struct SessionReplay {
+	state: Rc<RefCell<ReplayState>>,
+}
+
+struct ReplayState {
+	events: Vec<SessionEvent>,
+	position: usize,
+}
+
+impl SessionReplay {
+	/// Initializer from already-recorded events
+	pub fn new(events: Vec<SessionEvent>) -> Self {
+		Self {
+			state: Rc::new(RefCell::new(ReplayState {
+				events,
+				position: 0,
+			})),
+		}
+	}
+	/// Parses a recording created by `SessionRecorder::to_text()`
+	pub fn from_text(text: &str) -> ReadResult<Self> {
+		let mut events = vec!();
+		for (i, line) in text.lines().enumerate() {
+			let Some(event) = SessionEvent::from_line(line) else {
+				return Err(SmartReadError::Other(format!("invalid session recording, line {} could not be parsed: {line:?}", i + 1).into()));
+			};
+			events.push(event);
+		}
+		Ok(Self::new(events))
+	}
+	/// Loads a recording saved by `SessionRecorder::save()`
+	pub fn load(path: impl AsRef<Path>) -> ReadResult<Self> {
+		Self::from_text(&std::fs::read_to_string(path)?)
+	}
+	/// Returns whether every recorded event has been replayed
+	pub fn is_finished(&self) -> bool {
+		let state = self.state.borrow();
+		state.position >= state.events.len()
+	}
+	/// Returns the input that should be given for the current prompt, without moving to the next event
+	pub fn peek_answer(&self) -> ReadResult<String> {
+		let state = self.state.borrow();
+		match state.events.get(state.position) {
+			Some(SessionEvent::Input(input)) => Ok(input.clone()),
+			Some(SessionEvent::DefaultUsed) => Ok(String::new()),
+			Some(event) => Err(SmartReadError::ReplayDiverged {step: state.position + 1, expected: event.to_string(), found: String::from("input")}),
+			None => Err(SmartReadError::ReplayDiverged {step: state.position + 1, expected: String::from("end of recording"), found: String::from("input")}),
+		}
+	}
+	/// Checks that the given event is the next one in the recording, and moves to the next event if so
+	pub fn check(&self, event: &SessionEvent) -> ReadResult<()> {
+		let mut state = self.state.borrow_mut();
+		let step = state.position + 1;
+		match state.events.get(state.position) {
+			Some(expected) if expected == event => {
+				state.position += 1;
+				Ok(())
+			}
+			Some(expected) => Err(SmartReadError::ReplayDiverged {step, expected: expected.to_string(), found: event.to_string()}),
+			None => Err(SmartReadError::ReplayDiverged {step, expected: String::from("end of recording"), found: event.to_string()}),
+		}
+	}
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use basics::UsizeInput;
+	use secret::SecretInput;
+	use testing::ScriptedTerminal;
+	
+	// answers a name, a number (with a default), and a password
+	fn run_session(term: &mut Terminal) -> ReadResult<(String, usize, String)> {
+		let name = Prompt::new("Name: ").try_read_with(term, ())?;
+		let count = Prompt::new("Count: ").default(3_usize).try_read_with(term, UsizeInput)?;
+		let password = Prompt::new("Password: ").try_read_with(term, SecretInput::new())?;
+		Ok((name, count, password.expose_secret().to_string()))
+	}
+	
+	#[test]
+	fn recordings_can_be_replayed() {
+		let recorder = SessionRecorder::new();
+		let script = ScriptedTerminal::new(&["alice", "", "hunter2"]);
+		let mut term = script.terminal();
+		term.recorder = Some(recorder.clone());
+		assert!(run_session(&mut term).is_ok());
+		assert!(!recorder.to_text().contains("hunter2"));
+		
+		// secrets aren't recorded, so they're still read from the input
+		let replay = SessionReplay::from_text(&recorder.to_text());
+		let replay = replay.ok();
+		let script = ScriptedTerminal::new(&["swordfish"]);
+		let mut term = script.terminal();
+		term.replay = replay.clone();
+		let output = run_session(&mut term);
+		assert_eq!(output.ok(), Some((String::from("alice"), 3, String::from("swordfish"))));
+		assert!(replay.is_some_and(|replay| replay.is_finished()));
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn different_prompts_diverge() {
+		let replay = SessionReplay::new(vec!(SessionEvent::Prompt(String::from("Name: ")), SessionEvent::Input(String::from("alice"))));
+		let mut term = ScriptedTerminal::new::<&str>(&[]).terminal();
+		term.replay = Some(replay);
+		let output = Prompt::new("Email: ").try_read_with(&mut term, ());
+		assert!(matches!(&output, Err(SmartReadError::ReplayDiverged {step: 1, expected, found}) if expected == "prompt \"Name: \"" && found == "prompt \"Email: \""), "{output:?}");
+	}
+	
+	#[test]
+	fn reading_past_the_end_diverges() {
+		let replay = SessionReplay::new(vec!(SessionEvent::Prompt(String::from("Name: ")), SessionEvent::Input(String::from("alice"))));
+		let mut term = ScriptedTerminal::new::<&str>(&[]).terminal();
+		term.replay = Some(replay);
+		assert_eq!(Prompt::new("Name: ").try_read_with(&mut term, ()).ok().as_deref(), Some("alice"));
+		let output = Prompt::new("Name: ").try_read_with(&mut term, ());
+		assert!(matches!(&output, Err(SmartReadError::ReplayDiverged {step: 3, expected, ..}) if expected == "end of recording"), "{output:?}");
+	}
+	
+	#[test]
+	fn secrets_diverge_from_recorded_inputs() {
+		let replay = SessionReplay::new(vec!(SessionEvent::Prompt(String::from("Password: ")), SessionEvent::Input(String::from("hunter2"))));
+		let mut term = ScriptedTerminal::new(&["swordfish"]).terminal();
+		term.replay = Some(replay);
+		let output = Prompt::new("Password: ").try_read_with(&mut term, SecretInput::new());
+		assert!(matches!(&output, Err(SmartReadError::ReplayDiverged {step: 2, found, ..}) if found == "secret input"), "{output:?}");
+	}
+	
+	#[test]
+	fn events_round_trip_through_text() {
+		let events = vec!(
+			SessionEvent::Prompt(String::from("Multi\nline\tprompt \\ ")),
+			SessionEvent::Input(String::new()),
+			SessionEvent::DefaultUsed,
+			SessionEvent::SuggestionAccepted(String::from("Windrip")),
+			SessionEvent::SecretInput,
+		);
+		let text = events.iter().map(|event| event.to_line() + "\n").collect::<String>();
+		assert_eq!(text.lines().count(), events.len());
+		assert_eq!(text.lines().map(SessionEvent::from_line).collect::<Vec<_>>(), events.into_iter().map(Some).collect::<Vec<_>>());
+		assert!(SessionReplay::from_text("nonsense").is_err());
+	}
+}
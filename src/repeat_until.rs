@@ -0,0 +1,288 @@
+use crate::*;
+
+
+
+/// Keeps reading values, one per line, until a blank line is entered
+/// 
+/// Each value goes through the element type's parsing, validation, and retries. The element type can be anything that implements `PromptHooks`
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// # use smart_read::prelude::*;
+/// let items = prompt!("Enter the items:"; RepeatUntil::new(NonEmptyInput).min_count(1).commands());
+/// ```
+/// 
+/// Which looks like this:
+/// 
+/// ```text
+/// Enter the items:
+/// Enter "undo" to remove the last value, or "list" to show every value
+/// Item 1 (blank to finish): apples
+/// Item 2 (blank to finish): pears
+/// Item 3 (blank to finish): undo
+/// Removed "pears"
+/// Item 2 (blank to finish):
+/// ```
+/// 
+/// A preset answer (see `Terminal::prompt_id`) gives every value at once, one value per line, so `SMART_READ_ANSWER_ITEMS="apples\npears"` or `items = "apples\npears"` in an answer file answers `RepeatUntil` with the prompt id `"items"`. Each value goes through the same parsing and validation as typed values, and an invalid value or the wrong number of values makes the read return `SmartReadError::InvalidAnswer`. If there is no preset answer, the values are typed one at a time as usual (or `SmartReadError::MissingAnswer` is returned if the answer file doesn't allow falling back to typed input)
+#[derive(Clone)]
+pub struct RepeatUntil<T: PromptHooks> {
+	/// The type of each value
+	pub element: T,
+	/// How many values have to be entered before a blank line is accepted
+	pub min_count: Option<usize>,
+	/// The most values that can be entered, once this many are entered the read finishes automatically
+	pub max_count: Option<usize>,
+	/// Whether "undo" and "list" can be entered to remove the last value or show every value
+	pub commands: bool,
+}
+
+impl<T: PromptHooks> RepeatUntil<T> {
+	/// Creates a `RepeatUntil` with no limits on the number of values
+	pub fn new(element: T) -> Self {
+		Self {
+			element,
+			min_count: None,
+			max_count: None,
+			commands: false,
+		}
+	}
+	/// Sets how many values have to be entered before a blank line is accepted
+	pub fn min_count(mut self, min_count: usize) -> Self {
+		self.min_count = Some(min_count);
+		self
+	}
+	/// Sets the most values that can be entered
+	pub fn max_count(mut self, max_count: usize) -> Self {
+		self.max_count = Some(max_count);
+		self
+	}
+	/// Allows "undo" and "list" to be entered to remove the last value or show every value
+	pub fn commands(mut self) -> Self {
+		self.commands = true;
+		self
+	}
+	fn read_preset_values(&self, term: &mut Terminal, prompt: Option<String>, preset_answer: PresetAnswer) -> ReadResult<Vec<T::Output>> {
+		let PresetAnswer {source, answer} = preset_answer;
+		if let Some(prompt) = prompt {
+			term.print_prompt(&prompt)?;
+			term.println("")?;
+		}
+		let mut values = vec!();
+		for line in answer.lines().filter(|line| !line.trim().is_empty()) {
+			let item_prompt = format!("Item {} (blank to finish): ", values.len() + 1);
+			term.log_event(SessionEvent::Prompt(item_prompt.clone()))?;
+			term.print_prompt(&item_prompt)?;
+			term.println(&format!("{line} (from {source})"))?;
+			term.log_event(SessionEvent::Input(line.to_string()))?;
+			match self.element.parse_and_validate(line.to_string()) {
+				Ok(value) => values.push(value),
+				Err(reason) => return Err(SmartReadError::InvalidAnswer {source, answer: line.to_string(), reason}),
+			}
+		}
+		let min_count = self.min_count.unwrap_or(0);
+		let count_error = match self.max_count {
+			_ if values.len() < min_count => Some(format!("Invalid input, must enter at least {min_count} {}", if min_count == 1 {"value"} else {"values"})),
+			Some(max_count) if values.len() > max_count => Some(format!("Invalid input, must enter at most {max_count} {}", if max_count == 1 {"value"} else {"values"})),
+			_ => None,
+		};
+		if let Some(reason) = count_error {
+			return Err(SmartReadError::InvalidAnswer {source, answer, reason});
+		}
+		// the blank line that finishes a typed read is recorded too, so that the recording can be replayed
+		if self.max_count.is_none_or(|max_count| values.len() < max_count) {
+			term.log_event(SessionEvent::Prompt(format!("Item {} (blank to finish): ", values.len() + 1)))?;
+			term.log_event(SessionEvent::DefaultUsed)?;
+		}
+		Ok(values)
+	}
+	fn read_values(&self, term: &mut Terminal, prompt: Option<String>, default: Option<Vec<T::Output>>) -> ReadResult<Vec<T::Output>> {
+		if let Some(mut prompt) = prompt {
+			if let Some(default) = default.as_ref() {
+				let default = default.iter().map(|value| self.element.render_default(value)).collect::<Vec<_>>().join(", ");
+				prompt += &format!(" (default: {default})");
+			}
+			term.print_prompt(&prompt)?;
+			term.println("")?;
+		}
+		if self.commands {
+			term.print_note("Enter \"undo\" to remove the last value, or \"list\" to show every value")?;
+		}
+		let min_count = self.min_count.unwrap_or(0);
+		let mut values = vec!();
+		let mut retries = RetryCounter::new(term);
+		loop {
+			if let Some(max_count) = self.max_count && values.len() >= max_count {
+				return Ok(values);
+			}
+			
+			// a blank line is only allowed once enough values are entered, so timeouts and non-interactive mode can only finish the read when it's allowed
+			let can_finish = values.len() >= min_count || (values.is_empty() && default.is_some());
+			let input = term.prompt_line(&format!("Item {} (blank to finish): ", values.len() + 1), can_finish)?;
+			
+			if input.is_empty() {
+				if values.is_empty() && let Some(default) = default {
+					return Ok(default);
+				}
+				if values.len() >= min_count {
+					return Ok(values);
+				}
+				let message = format!("Invalid input, must enter at least {min_count} {}", if min_count == 1 {"value"} else {"values"});
+				retries.invalid_input(term, &message, &mut None::<()>)?;
+				continue;
+			}
+			if self.commands && input.trim().eq_ignore_ascii_case("undo") {
+				match values.pop() {
+					Some(value) => term.print_note(&format!("Removed \"{}\"", self.element.render_default(&value)))?,
+					None => term.print_note("There are no values to remove")?,
+				}
+				continue;
+			}
+			if self.commands && input.trim().eq_ignore_ascii_case("list") {
+				if values.is_empty() {
+					term.print_note("No values have been entered")?;
+				}
+				for (i, value) in values.iter().enumerate() {
+					term.println(&format!("{}: {}", i + 1, self.element.render_default(value)))?;
+				}
+				continue;
+			}
+			
+			match self.element.parse_and_validate(input) {
+				Ok(value) => {
+					values.push(value);
+					retries = RetryCounter::new(term);
+				}
+				Err(error_message) => {
+					retries.invalid_input(term, &error_message, &mut None::<()>)?;
+				}
+			}
+		}
+	}
+}
+
+impl<T: PromptHooks> TryRead for RepeatUntil<T> {
+	type Output = Vec<T::Output>;
+	type Default = Vec<T::Output>;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		// like `Terminal::prompt_line()`, a replay is used over preset answers
+		let preset_answer = match &term.replay {
+			Some(_) => None,
+			None => term.find_preset_answer(prompt.as_deref().unwrap_or("Items"))?,
+		};
+		// the values are read without the prompt id and answer file, since they would give the same answer for every value
+		let prompt_id = term.prompt_id.take();
+		let answer_file = term.answer_file.take();
+		let output = match preset_answer {
+			Some(preset_answer) => self.read_preset_values(term, prompt, preset_answer),
+			None => self.read_values(term, prompt, default),
+		};
+		term.prompt_id = prompt_id;
+		term.answer_file = answer_file;
+		output
+	}
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use basics::{NonEmptyInput, UsizeInput};
+	use preset_answers::AnswerFile;
+	use std::collections::HashMap;
+	use testing::ScriptedTerminal;
+	
+	fn answer_file(answers: &[(&str, &str)]) -> AnswerFile {
+		AnswerFile::new(answers.iter().map(|(key, answer)| (key.to_string(), answer.to_string())).collect::<HashMap<_, _>>(), "answers.toml")
+	}
+	
+	fn strings(values: &[&str]) -> Vec<String> {
+		values.iter().map(|value| value.to_string()).collect()
+	}
+	
+	#[test]
+	fn values_are_read_until_a_blank_line() {
+		let script = ScriptedTerminal::new(&["apples", "", "pears", ""]);
+		let output = Prompt::new("Items").try_read_with(&mut script.terminal(), RepeatUntil::new(NonEmptyInput).min_count(2));
+		assert_eq!(output.ok(), Some(strings(&["apples", "pears"])));
+		script.assert_shown("Invalid input, must enter at least 2 values");
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn reads_finish_at_the_max_count() {
+		let script = ScriptedTerminal::new(&["1", "x", "2", "3"]);
+		let output = Prompt::new("Numbers").try_read_with(&mut script.terminal(), RepeatUntil::new(UsizeInput).max_count(2));
+		assert_eq!(output.ok(), Some(vec!(1, 2)));
+		script.assert_shown("Could not parse input");
+		assert_eq!(script.remaining_answers(), 1);
+	}
+	
+	#[test]
+	fn commands_undo_and_list_values() {
+		let script = ScriptedTerminal::new(&["undo", "apples", "pears", "list", "UNDO", "plums", ""]);
+		let output = Prompt::new("Items").try_read_with(&mut script.terminal(), RepeatUntil::new(NonEmptyInput).commands());
+		assert_eq!(output.ok(), Some(strings(&["apples", "plums"])));
+		script.assert_shown("There are no values to remove");
+		script.assert_shown("1: apples\n2: pears\n");
+		script.assert_shown("Removed \"pears\"");
+		script.assert_all_consumed();
+		
+		// without `commands()`, they're just values
+		let script = ScriptedTerminal::new(&["undo", "list", ""]);
+		let output = Prompt::new("Items").try_read_with(&mut script.terminal(), RepeatUntil::new(NonEmptyInput));
+		assert_eq!(output.ok(), Some(strings(&["undo", "list"])));
+	}
+	
+	#[test]
+	fn preset_answers_give_every_value() {
+		let script = ScriptedTerminal::new(&["typed"]);
+		let answers = answer_file(&[("items", "apples\n\npears\n")]);
+		let output = Prompt::new("Items").id("items").answer_file(answers.clone()).try_read_with(&mut script.terminal(), RepeatUntil::new(NonEmptyInput).min_count(1));
+		assert_eq!(output.ok(), Some(strings(&["apples", "pears"])));
+		script.assert_shown("Item 2 (blank to finish): pears (from answers.toml (key \"items\"))");
+		assert_eq!(script.remaining_answers(), 1);
+		assert!(answers.finish().is_ok());
+	}
+	
+	#[test]
+	fn invalid_preset_answers_are_errors() {
+		let script = ScriptedTerminal::new(&["1", ""]);
+		let answers = answer_file(&[("ports", "80\nhttp"), ("hosts", "a\nb\nc")]);
+		let output = Prompt::new("Ports").id("ports").answer_file(answers.clone()).try_read_with(&mut script.terminal(), RepeatUntil::new(UsizeInput));
+		assert!(matches!(&output, Err(SmartReadError::InvalidAnswer {answer, ..}) if answer == "http"), "{output:?}");
+		let output = Prompt::new("Hosts").id("hosts").answer_file(answers).try_read_with(&mut script.terminal(), RepeatUntil::new(NonEmptyInput).max_count(2));
+		assert!(matches!(&output, Err(SmartReadError::InvalidAnswer {reason, ..}) if reason == "Invalid input, must enter at most 2 values"), "{output:?}");
+		assert_eq!(script.remaining_answers(), 2);
+	}
+	
+	#[test]
+	fn missing_answers_are_errors_with_a_strict_answer_file() {
+		let script = ScriptedTerminal::new(&["apples", ""]);
+		let output = Prompt::new("Items").id("items").answer_file(answer_file(&[])).try_read_with(&mut script.terminal(), RepeatUntil::new(NonEmptyInput));
+		assert!(matches!(&output, Err(SmartReadError::MissingAnswer {prompt}) if prompt == "items"), "{output:?}");
+		
+		// with a fallback, the values are typed
+		let output = Prompt::new("Items").id("items").answer_file(answer_file(&[]).with_fallback()).try_read_with(&mut script.terminal(), RepeatUntil::new(NonEmptyInput));
+		assert_eq!(output.ok(), Some(strings(&["apples"])));
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn preset_answers_can_be_replayed() {
+		let recorder = SessionRecorder::new();
+		let mut term = ScriptedTerminal::new::<&str>(&[]).terminal();
+		term.recorder = Some(recorder.clone());
+		let output = Prompt::new("Items").id("items").answer_file(answer_file(&[("items", "apples\npears")])).try_read_with(&mut term, RepeatUntil::new(NonEmptyInput));
+		assert_eq!(output.ok(), Some(strings(&["apples", "pears"])));
+		
+		let mut term = ScriptedTerminal::new::<&str>(&[]).terminal();
+		term.replay = SessionReplay::from_text(&recorder.to_text()).ok();
+		let output = Prompt::new("Items").try_read_with(&mut term, RepeatUntil::new(NonEmptyInput));
+		assert_eq!(output.ok(), Some(strings(&["apples", "pears"])));
+		assert!(term.replay.is_some_and(|replay| replay.is_finished()));
+	}
+}
@@ -0,0 +1,214 @@
+use crate::*;
+use std::{marker::PhantomData, str::FromStr};
+
+
+
+/// Reads values from a line that matches a template, where each `{}` is a value and everything else has to match exactly (use `{{` and `}}` for literal braces)
+/// 
+/// The values are returned as a tuple, and each one is parsed with `FromStr`. Whitespace around values is ignored
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// # use smart_read::prelude::*;
+/// let (width, height, fps): (u32, u32, f64) = prompt!("Enter the resolution and framerate: "; scan "{}x{} @ {}fps");
+/// // is the same as:
+/// let (width, height, fps): (u32, u32, f64) = prompt!("Enter the resolution and framerate: "; Scan::new("{}x{} @ {}fps"));
+/// ```
+/// 
+/// If the template is invalid or doesn't have one `{}` for each value, the read returns `SmartReadError::Other`
+pub struct Scan<T> {
+	template: String,
+	literals: Result<Vec<String>, String>,
+	_fields: PhantomData<fn() -> T>,
+}
+
+impl<T> Scan<T> {
+	/// Basic initializer
+	pub fn new(template: impl Into<String>) -> Self {
+		let template = template.into();
+		Self {
+			literals: parse_template(&template),
+			template,
+			_fields: PhantomData,
+		}
+	}
+}
+
+impl<T> Clone for Scan<T> {
+	fn clone(&self) -> Self {
+		Self {
+			template: self.template.clone(),
+			literals: self.literals.clone(),
+			_fields: PhantomData,
+		}
+	}
+}
+
+impl<T: ScanFields> PromptHooks for Scan<T> {
+	type Output = T;
+	fn default_prompt(&self) -> String {
+		format!("Enter input in the format \"{}\": ", self.template)
+	}
+	fn render_default(&self, default: &Self::Output) -> String {
+		let Ok(literals) = &self.literals else {return String::new();};
+		let mut output = literals[0].clone();
+		for (field, literal) in default.render_fields().iter().zip(&literals[1..]) {
+			output += field;
+			output += literal;
+		}
+		output
+	}
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		let literals = self.literals.as_ref()?;
+		
+		// find where each field starts and ends
+		let Some(mut position) = input.starts_with(&*literals[0]).then_some(literals[0].len()) else {
+			return Err(error_at(&input, 0, &format!("Invalid input, expected {:?}", literals[0])));
+		};
+		let mut field_ranges = vec!();
+		for (i, literal) in literals.iter().enumerate().skip(1) {
+			let is_last = i == literals.len() - 1;
+			let field_end = if is_last {
+				input.ends_with(&**literal).then(|| input.len() - literal.len()).filter(|&field_end| field_end >= position)
+			} else {
+				input[position..].find(&**literal).map(|offset| position + offset)
+			};
+			let Some(field_end) = field_end else {
+				let message = if literal.is_empty() {String::from("Invalid input, expected a value")} else {format!("Invalid input, expected {literal:?}")};
+				return Err(error_at(&input, position, &message));
+			};
+			field_ranges.push(position..field_end);
+			position = field_end + literal.len();
+		}
+		
+		let fields = field_ranges.iter().map(|range| input[range.clone()].trim()).collect::<Vec<_>>();
+		T::parse_fields(&fields).map_err(|(i, error)| {
+			error_at(&input, field_ranges[i].start, &format!("Could not parse value #{} ({:?}) (error: {error})", i + 1, fields[i]))
+		})
+	}
+}
+
+impl<T: ScanFields> TryRead for Scan<T> {
+	type Output = T;
+	type Default = T;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		let field_count = match &self.literals {
+			Ok(literals) => literals.len() - 1,
+			Err(error) => return Err(SmartReadError::Other(error.clone().into())),
+		};
+		if field_count != T::LEN {
+			return Err(SmartReadError::Other(format!("invalid scan template {:?}, it has {field_count} values but the output has {}", self.template, T::LEN).into()));
+		}
+		run_prompt_loop(term, &self, prompt, default)
+	}
+}
+
+
+
+// splits the template into the literal text around each `{}`, so there is always one more literal than there are values
+fn parse_template(template: &str) -> Result<Vec<String>, String> {
+	let mut literals = vec!();
+	let mut current = String::new();
+	let mut chars = template.chars().peekable();
+	while let Some(c) = chars.next() {
+		match (c, chars.peek()) {
+			('{', Some('{')) | ('}', Some('}')) => {
+				chars.next();
+				current.push(c);
+			}
+			('{', Some('}')) => {
+				chars.next();
+				if !literals.is_empty() && current.is_empty() {
+					return Err(format!("invalid scan template {template:?}, values must be separated by some text"));
+				}
+				literals.push(std::mem::take(&mut current));
+			}
+			('{', _) | ('}', _) => return Err(format!("invalid scan template {template:?}, use `{{{{` and `}}}}` for literal braces")),
+			(c, _) => current.push(c),
+		}
+	}
+	literals.push(current);
+	Ok(literals)
+}
+
+
+/// Tuples of values that `Scan` can read, this is implemented for tuples of 1 to 6 types that implement `FromStr` and `Display`
+pub trait ScanFields: Sized {
+	/// How many values are in the tuple
+	const LEN: usize;
+	/// Parses each value, or returns the index of the value that couldn't be parsed and the error
+	fn parse_fields(fields: &[&str]) -> Result<Self, (usize, String)>;
+	/// Formats each value
+	fn render_fields(&self) -> Vec<String>;
+}
+
+macro_rules! implement_scan_fields {
+	($len:expr; $($type:ident $index:tt),*) => {
+		impl<$($type),*> ScanFields for ($($type,)*)
+		where
+			$($type: FromStr + Display, <$type as FromStr>::Err: Display,)*
+		{
+			const LEN: usize = $len;
+			fn parse_fields(fields: &[&str]) -> Result<Self, (usize, String)> {
+				Ok(($(fields[$index].parse::<$type>().map_err(|err| ($index, err.to_string()))?,)*))
+			}
+			fn render_fields(&self) -> Vec<String> {
+				vec!($(self.$index.to_string()),*)
+			}
+		}
+	};
+}
+
+implement_scan_fields!(1; A 0);
+implement_scan_fields!(2; A 0, B 1);
+implement_scan_fields!(3; A 0, B 1, C 2);
+implement_scan_fields!(4; A 0, B 1, C 2, D 3);
+implement_scan_fields!(5; A 0, B 1, C 2, D 3, E 4);
+implement_scan_fields!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use testing::ScriptedTerminal;
+	
+	fn parse<T: ScanFields>(template: &str, input: &str) -> Result<T, String> {
+		Scan::<T>::new(template).parse(input.to_string())
+	}
+	
+	#[test]
+	fn values_are_parsed() {
+		assert_eq!(parse::<(u32, u32, f64)>("{}x{} @ {}fps", "1920x1080 @ 59.94fps"), Ok((1920, 1080, 59.94)));
+		assert_eq!(parse::<(u8, u8)>("{{{}, {}}}", "{ 1 ,  2}"), Ok((1, 2)));
+	}
+	
+	#[test]
+	fn errors_point_at_the_problem() {
+		assert_eq!(parse::<(u32, u32)>("[{},{}]", "(1,2]"), Err(error_at("(1,2]", 0, "Invalid input, expected \"[\"")));
+		assert_eq!(parse::<(u32, u32)>("[{},{}]", "[1;2]"), Err(error_at("[1;2]", 1, "Invalid input, expected \",\"")));
+		assert_eq!(parse::<(u32, u32)>("[{},{}]", "[1,2"), Err(error_at("[1,2", 3, "Invalid input, expected \"]\"")));
+		assert_eq!(parse::<(u32, u32)>("{}x{}", "12x"), Err(error_at("12x", 3, "Could not parse value #2 (\"\") (error: cannot parse integer from empty string)")));
+		assert_eq!(parse::<(u32, u32)>("{}x{}", "12xab"), Err(error_at("12xab", 3, "Could not parse value #2 (\"ab\") (error: invalid digit found in string)")));
+	}
+	
+	#[test]
+	fn invalid_templates_are_errors() {
+		let script = ScriptedTerminal::new(&["1,2"]);
+		let output = Scan::<(u32, u32)>::new("{}{}").try_read_with(&mut script.terminal(), None, None);
+		assert!(matches!(output, Err(SmartReadError::Other(_))));
+		let output = Scan::<(u32, u32)>::new("{}").try_read_with(&mut script.terminal(), None, None);
+		assert!(matches!(output, Err(SmartReadError::Other(_))));
+		assert_eq!(script.used_answers(), 0);
+	}
+	
+	#[test]
+	fn invalid_input_is_asked_for_again() {
+		let script = ScriptedTerminal::new(&["12xab", "12x34"]);
+		let output = Scan::<(u32, u32)>::new("{}x{}").try_read_with(&mut script.terminal(), None, None);
+		assert_eq!(output.ok(), Some((12, 34)));
+		script.assert_shown("12xab\n   ^");
+	}
+}
@@ -0,0 +1,371 @@
+use crate::*;
+use std::{convert::Infallible, mem::MaybeUninit, sync::atomic::{compiler_fence, Ordering}};
+
+
+
+/// Reads a password (or any other secret) without showing what's typed, as a `SecretString`
+/// 
+/// The terminal's echo is turned off while the secret is typed, and it's turned back on afterwards, even if the read panics or Ctrl-C is pressed. Preset answers are shown as `(hidden)`, and recordings only have `SessionEvent::SecretInput` instead of the secret
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// # use smart_read::prelude::*;
+/// let password = prompt!("New password: "; SecretInput::new().min_len(8).require_digit().confirm("Confirm password: "));
+/// let password_len = password.expose_secret().len();
+/// ```
+/// 
+/// Which looks like this:
+/// 
+/// ```text
+/// New password:
+/// Confirm password:
+/// 
+/// Invalid input, the entries don't match
+/// New password:
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SecretInput {
+	/// The fewest characters the secret can have
+	pub min_len: usize,
+	/// Whether the secret has to contain a lowercase letter
+	pub require_lowercase: bool,
+	/// Whether the secret has to contain an uppercase letter
+	pub require_uppercase: bool,
+	/// Whether the secret has to contain a digit
+	pub require_digit: bool,
+	/// Whether the secret has to contain a character that isn't a letter, digit, or whitespace
+	pub require_symbol: bool,
+	/// If this is set, the secret has to be entered again with this prompt, and both entries have to match
+	pub confirm_prompt: Option<String>,
+	/// What to do when the input can't be hidden, for example when stdin isn't a terminal
+	pub non_tty_policy: NonTtyPolicy,
+}
+
+/// What a `SecretInput` does when the input can't be hidden, for example when stdin isn't a terminal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NonTtyPolicy {
+	/// Read the secret as a normal line, this is the default since piped input isn't shown anyway
+	/// 
+	/// This also means that a secret is read from stdin even when stdin isn't a terminal (which would otherwise make the read non-interactive), so `echo "$PASSWORD" | my-app` works. Non-interactive mode that's set explicitly (see `Terminal::is_non_interactive()`) still applies
+	#[default]
+	ReadPlainLines,
+	/// Return `SmartReadError::CannotHideInput`, even if the read would otherwise be non-interactive because stdin isn't a terminal
+	Refuse,
+}
+
+impl SecretInput {
+	/// Basic initializer, any secret is allowed (including an empty one)
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Sets the fewest characters the secret can have
+	pub fn min_len(mut self, min_len: usize) -> Self {
+		self.min_len = min_len;
+		self
+	}
+	/// Requires the secret to contain a lowercase letter
+	pub fn require_lowercase(mut self) -> Self {
+		self.require_lowercase = true;
+		self
+	}
+	/// Requires the secret to contain an uppercase letter
+	pub fn require_uppercase(mut self) -> Self {
+		self.require_uppercase = true;
+		self
+	}
+	/// Requires the secret to contain a digit
+	pub fn require_digit(mut self) -> Self {
+		self.require_digit = true;
+		self
+	}
+	/// Requires the secret to contain a character that isn't a letter, digit, or whitespace
+	pub fn require_symbol(mut self) -> Self {
+		self.require_symbol = true;
+		self
+	}
+	/// Makes the secret be entered again with the given prompt, and both entries have to match
+	pub fn confirm(mut self, prompt: impl Into<String>) -> Self {
+		self.confirm_prompt = Some(prompt.into());
+		self
+	}
+	/// Sets what to do when the input can't be hidden
+	pub fn non_tty_policy(mut self, non_tty_policy: NonTtyPolicy) -> Self {
+		self.non_tty_policy = non_tty_policy;
+		self
+	}
+	/// Checks the secret against the length and character requirements
+	pub fn validate(&self, secret: &str) -> Result<(), String> {
+		let len = secret.chars().count();
+		if len < self.min_len {
+			return Err(format!("Invalid input, must be at least {} {} long", self.min_len, if self.min_len == 1 {"character"} else {"characters"}));
+		}
+		if self.require_lowercase && !secret.chars().any(char::is_lowercase) {
+			return Err(String::from("Invalid input, must contain a lowercase letter"));
+		}
+		if self.require_uppercase && !secret.chars().any(char::is_uppercase) {
+			return Err(String::from("Invalid input, must contain an uppercase letter"));
+		}
+		if self.require_digit && !secret.chars().any(|c| c.is_ascii_digit()) {
+			return Err(String::from("Invalid input, must contain a digit"));
+		}
+		if self.require_symbol && !secret.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+			return Err(String::from("Invalid input, must contain a symbol"));
+		}
+		Ok(())
+	}
+}
+
+impl TryRead for SecretInput {
+	type Output = SecretString;
+	type Default = Infallible; // a default secret would have to be shown or guessed, so none can be given
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, _default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		let prompt = prompt.unwrap_or_else(|| String::from("Enter password: "));
+		let mut retries = RetryCounter::new(term);
+		let mut default = None;
+		loop {
+			
+			let input = term.prompt_secret_line(&prompt, self.non_tty_policy)?;
+			let error_message = match self.validate(input.expose_secret()) {
+				Ok(()) => {
+					let Some(confirm_prompt) = &self.confirm_prompt else {return Ok(input);};
+					if term.prompt_secret_line(confirm_prompt, self.non_tty_policy)? == input {return Ok(input);}
+					String::from("Invalid input, the entries don't match")
+				}
+				Err(error_message) => error_message,
+			};
+			if let Some(default) = retries.invalid_input(term, &error_message, &mut default)? {
+				return Ok(default);
+			}
+			
+		}
+	}
+}
+
+
+
+/// A string that's wiped from memory when it's dropped, and that's never shown by `Debug` or `Display`
+/// 
+/// Use `expose_secret()` to get the content, and avoid copying it into normal `String`s since those aren't wiped
+/// 
+/// ```
+/// use smart_read::secret::SecretString;
+/// 
+/// let token = SecretString::from(String::from("hunter2"));
+/// assert_eq!(format!("{token:?}"), "SecretString(hidden)");
+/// assert_eq!(token.expose_secret(), "hunter2");
+/// ```
+#[derive(Clone, Default)]
+pub struct SecretString(String);
+
+impl SecretString {
+	/// Returns the secret
+	pub fn expose_secret(&self) -> &str {
+		&self.0
+	}
+	/// Returns the length of the secret in bytes
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+	/// Returns whether the secret is empty
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+	// gives the string back without wiping it, for lines that turn out not to be secret
+	pub(crate) fn into_string(mut self) -> String {
+		std::mem::take(&mut self.0)
+	}
+}
+
+impl From<String> for SecretString {
+	/// Takes ownership of the string without copying it
+	fn from(secret: String) -> Self {
+		Self(secret)
+	}
+}
+
+impl Drop for SecretString {
+	fn drop(&mut self) {
+		// SAFETY: only zeros are written, which is valid utf-8
+		wipe(unsafe {self.0.as_mut_vec()});
+	}
+}
+
+// the whole capacity is wiped, since removed characters (like the trailing newline) are still in the buffer
+fn wipe(bytes: &mut Vec<u8>) {
+	for byte in bytes.iter_mut() {
+		// SAFETY: the pointer comes from a reference, volatile writes make sure the wipe isn't optimized away
+		unsafe {std::ptr::write_volatile(byte, 0);}
+	}
+	for byte in bytes.spare_capacity_mut() {
+		// SAFETY: same as above
+		unsafe {std::ptr::write_volatile(byte, MaybeUninit::new(0));}
+	}
+	compiler_fence(Ordering::SeqCst);
+}
+
+// makes room for more text by moving it to a bigger allocation and wiping the old one, since letting the string grow by itself would free the old allocation with the text still in it
+pub(crate) fn reserve_wiped(text: &mut String, additional: usize) {
+	if text.capacity() - text.len() >= additional {return;}
+	let mut new_text = String::with_capacity((text.len() + additional).max(text.capacity() * 2));
+	new_text.push_str(text);
+	drop(SecretString(std::mem::replace(text, new_text)));
+}
+
+
+
+// bytes that might be a secret, for reading a line before it's known to be valid utf-8
+#[derive(Default)]
+pub(crate) struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+	// same as `reserve_wiped()`, but for bytes
+	pub(crate) fn extend(&mut self, bytes: &[u8]) {
+		if self.0.capacity() - self.0.len() < bytes.len() {
+			let mut new_bytes = Vec::with_capacity((self.0.len() + bytes.len()).max(self.0.capacity() * 2));
+			new_bytes.extend_from_slice(&self.0);
+			drop(std::mem::replace(self, Self(new_bytes)));
+		}
+		self.0.extend_from_slice(bytes);
+	}
+	pub(crate) fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+	pub(crate) fn strip_suffix(&mut self, suffix: u8) {
+		if self.0.last() == Some(&suffix) {self.0.pop();}
+	}
+	// invalid utf-8 is wiped along with the rest of the bytes
+	pub(crate) fn into_string(mut self) -> Result<String, std::io::Error> {
+		if std::str::from_utf8(&self.0).is_err() {
+			return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "stream did not contain valid UTF-8"));
+		}
+		// SAFETY: the bytes were just checked
+		Ok(unsafe {String::from_utf8_unchecked(std::mem::take(&mut self.0))})
+	}
+}
+
+impl Drop for SecretBytes {
+	fn drop(&mut self) {
+		wipe(&mut self.0);
+	}
+}
+
+impl PartialEq for SecretString {
+	/// Compares in constant time (for secrets of the same length), so the comparison doesn't reveal how much of the secret matches
+	fn eq(&self, other: &Self) -> bool {
+		let (a, b) = (self.0.as_bytes(), other.0.as_bytes());
+		a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+	}
+}
+
+impl Eq for SecretString {}
+
+impl Debug for SecretString {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "SecretString(hidden)")
+	}
+}
+
+impl Display for SecretString {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "(hidden)")
+	}
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use testing::ScriptedTerminal;
+	
+	// like stdin when it's piped, so it can't hide the input and it isn't interactive
+	struct PipedInput(&'static [u8]);
+	
+	impl InputSource for PipedInput {
+		fn next_line(&mut self) -> Result<Option<String>, std::io::Error> {
+			self.0.next_line()
+		}
+		fn is_interactive(&self) -> bool {
+			false
+		}
+	}
+	
+	#[test]
+	fn piped_secret_is_read() {
+		let mut term = Terminal::new(PipedInput(b"hunter2\n"), std::io::sink());
+		let secret = SecretInput::new().try_read_with(&mut term, Some(String::from("pw: ")), None);
+		assert_eq!(secret.ok().as_ref().map(SecretString::expose_secret), Some("hunter2"));
+	}
+	
+	#[test]
+	fn long_piped_secret_is_read() {
+		let token = "a".repeat(1000);
+		let input = format!("{token}\n").leak().as_bytes();
+		let mut term = Terminal::new(PipedInput(input), std::io::sink());
+		let secret = SecretInput::new().try_read_with(&mut term, Some(String::from("token: ")), None);
+		assert_eq!(secret.ok().as_ref().map(SecretString::expose_secret), Some(token.as_str()));
+	}
+	
+	#[test]
+	fn lines_are_split_like_read_line() {
+		let mut input = "one\r\ntwo\n\nthree".as_bytes();
+		let lines = std::iter::from_fn(|| input.next_line().ok().flatten()).collect::<Vec<_>>();
+		assert_eq!(lines, ["one", "two", "", "three"]);
+		assert!(matches!(b"\xFF\n".as_slice().next_line(), Err(err) if err.kind() == std::io::ErrorKind::InvalidData));
+	}
+	
+	#[test]
+	fn reserve_wiped_keeps_the_text() {
+		let mut text = String::from("abc");
+		reserve_wiped(&mut text, 100);
+		assert_eq!(text, "abc");
+		assert!(text.capacity() >= 103);
+	}
+	
+	#[test]
+	fn piped_secret_can_be_refused() {
+		let mut term = Terminal::new(PipedInput(b"hunter2\n"), std::io::sink());
+		let secret = SecretInput::new().non_tty_policy(NonTtyPolicy::Refuse).try_read_with(&mut term, Some(String::from("pw: ")), None);
+		assert!(matches!(secret, Err(SmartReadError::CannotHideInput)), "{secret:?}");
+	}
+	
+	#[test]
+	fn explicit_non_interactive_mode_still_applies() {
+		let mut term = Terminal::new(PipedInput(b"hunter2\n"), std::io::sink());
+		term.non_interactive = Some(true);
+		let secret = SecretInput::new().try_read_with(&mut term, Some(String::from("pw: ")), None);
+		assert!(matches!(secret, Err(SmartReadError::NonInteractive {..})), "{secret:?}");
+	}
+	
+	#[test]
+	fn mismatched_confirmations_are_retried() {
+		let script = ScriptedTerminal::new(&["hunter22", "hunter23", "hunter22", "hunter22"]);
+		let input = SecretInput::new().confirm("confirm: ");
+		let secret = input.try_read_with(&mut script.terminal(), Some(String::from("pw: ")), None);
+		assert_eq!(secret.ok().as_ref().map(SecretString::expose_secret), Some("hunter22"));
+		script.assert_shown("pw: \nconfirm: \n\nInvalid input, the entries don't match\npw: \n");
+		script.assert_not_shown("hunter");
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn invalid_secrets_are_retried_before_confirming() {
+		let script = ScriptedTerminal::new(&["short", "nodigits", "hunter22", "hunter22"]);
+		let input = SecretInput::new().min_len(8).require_digit().confirm("confirm: ");
+		let secret = input.try_read_with(&mut script.terminal(), Some(String::from("pw: ")), None);
+		assert_eq!(secret.ok().as_ref().map(SecretString::expose_secret), Some("hunter22"));
+		script.assert_shown("Invalid input, must be at least 8 characters long");
+		script.assert_shown("Invalid input, must contain a digit");
+		assert_eq!(script.output().matches("confirm: ").count(), 1);
+	}
+	
+	#[test]
+	fn confirmations_use_the_retry_policy() {
+		let script = ScriptedTerminal::new(&["a", "b", "a", "c"]);
+		let secret = Prompt::new("pw: ").retries(2).try_read_with(&mut script.terminal(), SecretInput::new().confirm("confirm: "));
+		assert!(matches!(secret, Err(SmartReadError::RetriesExhausted {attempts: 2})), "{secret:?}");
+		script.assert_all_consumed();
+	}
+}
@@ -0,0 +1,171 @@
+use crate::*;
+use std::{collections::VecDeque, rc::Rc};
+
+
+
+/// In-memory terminal for testing code that uses `read!()`, `prompt!()`, `try_read_with()`, etc
+/// 
+/// Answers are given to the code being tested one line at a time, and everything the code prints (prompts, error messages, fuzzy-search suggestions, etc) is captured. Each answer is also added to the captured output (like a real terminal echoing the user's input), so the output reads like a transcript. Answers to secret inputs (see `SecretInput`) aren't added, since a real terminal wouldn't show them either
+/// 
+/// Once all the answers are used up, the input is treated as closed, so any further reads return `SmartReadError::Eof`
+/// 
+/// Example:
+/// 
+/// ```
+/// use smart_read::prelude::*;
+/// use smart_read::testing::ScriptedTerminal;
+/// 
+/// let script = ScriptedTerminal::new(&["abc", "101", "7"]);
+/// let input = script.run(|| prompt!("Enter a number: "; 1..=100));
+/// assert_eq!(input, 7);
+/// script.assert_shown("Could not parse input");
+/// script.assert_shown("Invalid input, not within bounds");
+/// script.assert_all_consumed();
+/// ```
+#[derive(Clone, Default)]
+pub struct ScriptedTerminal {
+	state: Rc<RefCell<ScriptState>>,
+}
+
+#[derive(Default)]
+struct ScriptState {
+	// `None` means that no answer is given before the timeout
+	answers: VecDeque<Option<String>>,
+	answers_used: usize,
+	output: String,
+	echo_disabled: bool,
+}
+
+impl ScriptedTerminal {
+	/// Basic initializer, each value is given as one line of input
+	pub fn new<T: ToString>(answers: &[T]) -> Self {
+		let output = Self::default();
+		for answer in answers {
+			output.push_answer(answer.to_string());
+		}
+		output
+	}
+	/// Adds another line of input after all the current ones
+	pub fn push_answer(&self, answer: impl ToString) {
+		self.state.borrow_mut().answers.push_back(Some(answer.to_string()));
+	}
+	/// Makes the next read time out instead of getting an answer, as if the user didn't type anything before the terminal's timeout (see `Terminal::timeout`)
+	pub fn push_timeout(&self) {
+		self.state.borrow_mut().answers.push_back(None);
+	}
+	/// Creates a `Terminal` which reads from and writes to this script
+	/// 
+	/// Any number of terminals can be created, and they all share the same answers and output
+	pub fn terminal(&self) -> Terminal<'static> {
+		Terminal::new(ScriptedInput(self.state.clone()), ScriptedOutput(self.state.clone()))
+	}
+	/// Runs the given function with this script as the default terminal (the one used by `read!()`, `prompt!()`, etc), then restores the previous default terminal
+	/// 
+	/// The previous terminal is restored even if the function panics, such as when `read!()` runs out of answers
+	pub fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+		let _guard = PrevTerminalGuard(Some(set_default_terminal(self.terminal())));
+		f()
+	}
+	/// Returns everything that has been written so far
+	pub fn output(&self) -> String {
+		self.state.borrow().output.clone()
+	}
+	/// Returns everything that has been written so far and clears it, which is useful for checking the output of each step separately
+	pub fn take_output(&self) -> String {
+		std::mem::take(&mut self.state.borrow_mut().output)
+	}
+	/// Returns the number of answers that have not been read yet
+	pub fn remaining_answers(&self) -> usize {
+		self.state.borrow().answers.len()
+	}
+	/// Returns the number of answers that have been read so far
+	pub fn used_answers(&self) -> usize {
+		self.state.borrow().answers_used
+	}
+	/// Returns whether the given text appears anywhere in the output
+	pub fn was_shown(&self, text: &str) -> bool {
+		self.state.borrow().output.contains(text)
+	}
+	/// Panics if the given text does not appear anywhere in the output
+	pub fn assert_shown(&self, text: &str) {
+		assert!(self.was_shown(text), "expected {text:?} to be shown, but the output was:\n{}", self.output());
+	}
+	/// Panics if the given text appears anywhere in the output
+	pub fn assert_not_shown(&self, text: &str) {
+		assert!(!self.was_shown(text), "expected {text:?} to not be shown, but the output was:\n{}", self.output());
+	}
+	/// Panics if there are any answers that have not been read yet
+	pub fn assert_all_consumed(&self) {
+		let state = self.state.borrow();
+		assert!(state.answers.is_empty(), "expected all scripted answers to be used, but {} remain: {:?}\noutput:\n{}", state.answers.len(), state.answers, state.output);
+	}
+}
+
+
+
+// puts the previous default terminal back when it's dropped, so that it's restored even if the function given to `run()` panics
+struct PrevTerminalGuard(Option<Terminal<'static>>);
+
+impl Drop for PrevTerminalGuard {
+	fn drop(&mut self) {
+		if let Some(prev_term) = self.0.take() {
+			set_default_terminal(prev_term);
+		}
+	}
+}
+
+
+
+struct ScriptedInput(Rc<RefCell<ScriptState>>);
+
+impl InputSource for ScriptedInput {
+	fn next_line(&mut self) -> Result<Option<String>, std::io::Error> {
+		let mut state = self.0.borrow_mut();
+		let Some(answer) = state.answers.pop_front() else {return Ok(None);};
+		state.answers_used += 1;
+		let Some(answer) = answer else {
+			return Err(std::io::Error::from(std::io::ErrorKind::TimedOut));
+		};
+		if !state.echo_disabled {
+			state.output += &answer;
+			state.output.push('\n');
+		}
+		Ok(Some(answer))
+	}
+	fn set_echo(&mut self, enabled: bool) -> Result<bool, std::io::Error> {
+		self.0.borrow_mut().echo_disabled = !enabled;
+		Ok(true)
+	}
+}
+
+struct ScriptedOutput(Rc<RefCell<ScriptState>>);
+
+impl OutputSink for ScriptedOutput {
+	fn write_text(&mut self, text: &str) -> Result<(), std::io::Error> {
+		self.0.borrow_mut().output += text;
+		Ok(())
+	}
+	fn flush_output(&mut self) -> Result<(), std::io::Error> {
+		Ok(())
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn default_terminal_is_restored_after_a_panic() {
+		let outer = ScriptedTerminal::new(&["outer"]);
+		let output = outer.run(|| {
+			let inner = ScriptedTerminal::new::<&str>(&[]);
+			#[allow(clippy::panic)] // REASON: this is what `read!()` does when the script runs out
+			let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| inner.run(|| ().try_read_line(None, None).unwrap_or_else(|err| panic!("{err}")))));
+			assert!(result.is_err());
+			().try_read_line(None, None)
+		});
+		assert_eq!(output.ok().as_deref(), Some("outer"));
+	}
+}
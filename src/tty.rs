@@ -0,0 +1,398 @@
+// Changes the mode of the terminal that stdin is connected to, for `StdinSource::set_echo()` and `LineEditor`
+//
+// The original mode is saved before the first change and put back by the next restore, and until then, Ctrl-C (and SIGTERM on unix) first restores the terminal, then does whatever it would have done otherwise
+
+
+
+#[cfg(unix)]
+pub use unix::set_stdin_echo;
+#[cfg(windows)]
+pub use windows::set_stdin_echo;
+
+#[cfg(not(any(unix, windows)))]
+pub fn set_stdin_echo(_enabled: bool) -> Result<bool, std::io::Error> {
+	Ok(false)
+}
+
+#[cfg(unix)]
+pub use unix::wait_for_stdin;
+#[cfg(windows)]
+pub use windows::wait_for_stdin;
+
+// timeouts aren't supported here, so input is always ready
+#[cfg(not(any(unix, windows)))]
+pub fn wait_for_stdin(_timeout: std::time::Duration) -> Result<bool, std::io::Error> {
+	Ok(true)
+}
+
+#[cfg(target_os = "linux")]
+pub use unix::{set_stdin_raw, read_stdin_byte, terminal_width};
+
+// whether `set_stdin_raw()` can put the terminal in raw mode, which needs the layout of `struct termios` to be known (see `unix::linux`)
+pub const RAW_MODE_SUPPORTED: bool = cfg!(all(target_os = "linux", any(target_arch = "x86", target_arch = "x86_64", target_arch = "arm", target_arch = "aarch64", target_arch = "riscv64", target_arch = "loongarch64", target_arch = "s390x")));
+
+// raw mode is only supported on linux, and `LineEditor` falls back to reading lines normally everywhere else
+#[cfg(not(target_os = "linux"))]
+pub fn set_stdin_raw(_enabled: bool) -> Result<bool, std::io::Error> {
+	Ok(false)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_stdin_byte(_timeout: Option<std::time::Duration>) -> Result<Option<u8>, std::io::Error> {
+	Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn terminal_width() -> Option<usize> {
+	None
+}
+
+
+
+#[cfg(unix)]
+mod unix {
+	use std::{cell::UnsafeCell, ffi::{c_int, c_short}, sync::atomic::{AtomicBool, AtomicUsize, Ordering}, time::Duration};
+
+	#[cfg(any(target_os = "macos", target_os = "ios"))]
+	type TcFlag = std::ffi::c_ulong;
+	#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+	type TcFlag = std::ffi::c_uint;
+
+	const STDIN_FILENO: c_int = 0;
+	const TCSANOW: c_int = 0;
+	// this is the same on every unix, unlike the flags that raw mode uses
+	const ECHO: TcFlag = 0o10;
+	const SIGINT: c_int = 2;
+	const SIGTERM: c_int = 15;
+	const SIG_DFL: usize = 0;
+	const SIG_IGN: usize = 1;
+	const SIG_ERR: usize = usize::MAX;
+	const POLLIN: c_short = 1;
+
+	// `struct termios` is laid out differently on every platform, but `c_lflag` is always the fourth field and this is bigger than all of them, so only `TcFlag` needs to be known
+	#[repr(C, align(8))]
+	#[derive(Clone, Copy)]
+	struct Termios([u8; 256]);
+
+	impl Termios {
+		fn flags(&mut self, index: usize) -> &mut TcFlag {
+			// SAFETY: the offset is within the buffer, and it's aligned because the buffer is aligned to 8 bytes
+			unsafe {&mut *self.0.as_mut_ptr().add(index * size_of::<TcFlag>()).cast::<TcFlag>()}
+		}
+		fn local_flags(&mut self) -> &mut TcFlag {
+			self.flags(3)
+		}
+	}
+
+	#[cfg(any(target_os = "macos", target_os = "ios"))]
+	type NFds = std::ffi::c_uint;
+	#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+	type NFds = std::ffi::c_ulong;
+
+	#[repr(C)]
+	struct PollFd {
+		fd: c_int,
+		events: c_short,
+		revents: c_short,
+	}
+
+	unsafe extern "C" {
+		fn isatty(fd: c_int) -> c_int;
+		fn poll(fds: *mut PollFd, nfds: NFds, timeout: c_int) -> c_int;
+		fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+		fn tcsetattr(fd: c_int, optional_actions: c_int, termios: *const Termios) -> c_int;
+		fn signal(signum: c_int, handler: usize) -> usize;
+		fn raise(signum: c_int) -> c_int;
+	}
+
+	struct SavedTermios(UnsafeCell<Termios>);
+	// SAFETY: this is only written while `STDIN_MODIFIED` is false, and only read while it is true
+	unsafe impl Sync for SavedTermios {}
+
+	static SAVED_TERMIOS: SavedTermios = SavedTermios(UnsafeCell::new(Termios([0; 256])));
+	static STDIN_MODIFIED: AtomicBool = AtomicBool::new(false);
+	static PREV_SIGINT_HANDLER: AtomicUsize = AtomicUsize::new(SIG_DFL);
+	static PREV_SIGTERM_HANDLER: AtomicUsize = AtomicUsize::new(SIG_DFL);
+
+	pub fn set_stdin_echo(enabled: bool) -> Result<bool, std::io::Error> {
+		// SAFETY: isatty() has no preconditions
+		if unsafe {isatty(STDIN_FILENO)} == 0 {return Ok(false);}
+		if enabled {
+			restore_stdin()?;
+		} else {
+			modify_stdin(|termios| *termios.local_flags() &= !ECHO)?;
+		}
+		Ok(true)
+	}
+
+	// returns false if nothing could be read from stdin before the timeout
+	pub fn wait_for_stdin(timeout: Duration) -> Result<bool, std::io::Error> {
+		let mut poll_fd = PollFd {fd: STDIN_FILENO, events: POLLIN, revents: 0};
+		let timeout = c_int::try_from(timeout.as_millis()).unwrap_or(c_int::MAX);
+		loop {
+			// SAFETY: there is exactly one pollfd
+			match unsafe {poll(&mut poll_fd, 1, timeout)} {
+				0 => return Ok(false),
+				result if result > 0 => return Ok(true),
+				_ => {
+					let err = std::io::Error::last_os_error();
+					if err.kind() != std::io::ErrorKind::Interrupted {return Err(err);}
+				}
+			}
+		}
+	}
+
+	fn modify_stdin(f: impl FnOnce(&mut Termios)) -> Result<(), std::io::Error> {
+		let mut termios = Termios([0; 256]);
+		// SAFETY: the buffer is bigger than `struct termios`
+		if unsafe {tcgetattr(STDIN_FILENO, &mut termios)} != 0 {return Err(std::io::Error::last_os_error());}
+		let is_first_change = !STDIN_MODIFIED.load(Ordering::SeqCst);
+		if is_first_change {
+			// SAFETY: the terminal hasn't been modified, so nothing else is reading this
+			unsafe {*SAVED_TERMIOS.0.get() = termios;}
+			STDIN_MODIFIED.store(true, Ordering::SeqCst);
+			install_signal_handler(SIGINT, &PREV_SIGINT_HANDLER);
+			install_signal_handler(SIGTERM, &PREV_SIGTERM_HANDLER);
+		}
+		f(&mut termios);
+		// SAFETY: the termios was filled by tcgetattr()
+		if unsafe {tcsetattr(STDIN_FILENO, TCSANOW, &termios)} != 0 {
+			let err = std::io::Error::last_os_error();
+			if is_first_change {
+				STDIN_MODIFIED.store(false, Ordering::SeqCst);
+				restore_signal_handlers();
+			}
+			return Err(err);
+		}
+		Ok(())
+	}
+
+	fn restore_stdin() -> Result<(), std::io::Error> {
+		if !STDIN_MODIFIED.swap(false, Ordering::SeqCst) {return Ok(());}
+		// SAFETY: the saved termios was filled by tcgetattr() before the terminal was modified
+		let result = unsafe {tcsetattr(STDIN_FILENO, TCSANOW, SAVED_TERMIOS.0.get())};
+		restore_signal_handlers();
+		if result != 0 {return Err(std::io::Error::last_os_error());}
+		Ok(())
+	}
+
+	fn install_signal_handler(signum: c_int, prev_handler: &AtomicUsize) {
+		// SAFETY: the handler only calls async-signal-safe functions
+		let prev = unsafe {signal(signum, restore_and_reraise as extern "C" fn(c_int) as usize)};
+		prev_handler.store(prev, Ordering::SeqCst);
+		// signals that are ignored can't leave the terminal broken, so they stay ignored
+		if prev == SIG_IGN {
+			// SAFETY: this puts back the handler that was just replaced
+			unsafe {signal(signum, SIG_IGN);}
+		}
+	}
+
+	fn restore_signal_handlers() {
+		// SAFETY: these are the handlers that were set before the terminal was modified
+		unsafe {
+			signal(SIGINT, prev_handler(SIGINT));
+			signal(SIGTERM, prev_handler(SIGTERM));
+		}
+	}
+
+	fn prev_handler(signum: c_int) -> usize {
+		let handler = if signum == SIGINT {&PREV_SIGINT_HANDLER} else {&PREV_SIGTERM_HANDLER};
+		match handler.load(Ordering::SeqCst) {
+			SIG_ERR => SIG_DFL,
+			handler => handler,
+		}
+	}
+
+	extern "C" fn restore_and_reraise(signum: c_int) {
+		// SAFETY: tcsetattr(), signal(), and raise() are async-signal-safe
+		unsafe {
+			if STDIN_MODIFIED.swap(false, Ordering::SeqCst) {
+				tcsetattr(STDIN_FILENO, TCSANOW, SAVED_TERMIOS.0.get());
+			}
+			signal(SIGINT, prev_handler(SIGINT));
+			signal(SIGTERM, prev_handler(SIGTERM));
+			raise(signum);
+		}
+	}
+
+
+
+	#[cfg(target_os = "linux")]
+	pub use linux::*;
+
+	#[cfg(target_os = "linux")]
+	mod linux {
+		use super::*;
+		use std::ffi::{c_ulong, c_void};
+
+		const STDOUT_FILENO: c_int = 1;
+		#[cfg(any(target_arch = "mips", target_arch = "mips64", target_arch = "powerpc", target_arch = "powerpc64", target_arch = "sparc64"))]
+		const TIOCGWINSZ: c_ulong = 0x40087468;
+		#[cfg(not(any(target_arch = "mips", target_arch = "mips64", target_arch = "powerpc", target_arch = "powerpc64", target_arch = "sparc64")))]
+		const TIOCGWINSZ: c_ulong = 0x5413;
+
+		#[repr(C)]
+		#[derive(Default)]
+		struct WinSize {
+			rows: u16,
+			cols: u16,
+			x_pixels: u16,
+			y_pixels: u16,
+		}
+
+		unsafe extern "C" {
+			fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+			fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+		}
+
+		// the flags and `c_cc` used by raw mode are only known for the architectures that use the kernel's generic `struct termios` (alpha, mips, powerpc, and sparc have their own), so the others keep the terminal in normal mode
+		#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "arm", target_arch = "aarch64", target_arch = "riscv64", target_arch = "loongarch64", target_arch = "s390x"))]
+		pub use generic_termios::set_stdin_raw;
+
+		#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "arm", target_arch = "aarch64", target_arch = "riscv64", target_arch = "loongarch64", target_arch = "s390x")))]
+		pub fn set_stdin_raw(_enabled: bool) -> Result<bool, std::io::Error> {
+			Ok(false)
+		}
+
+		#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "arm", target_arch = "aarch64", target_arch = "riscv64", target_arch = "loongarch64", target_arch = "s390x"))]
+		mod generic_termios {
+			use super::*;
+
+			const ICANON: TcFlag = 0o2;
+			const IEXTEN: TcFlag = 0o100000;
+			const IXON: TcFlag = 0o2000;
+			const VTIME: usize = 5;
+			const VMIN: usize = 6;
+			// `c_cc` comes after the four flags and `c_line`
+			const CONTROL_CHARS_OFFSET: usize = 4 * size_of::<TcFlag>() + 1;
+
+			impl Termios {
+				fn input_flags(&mut self) -> &mut TcFlag {
+					self.flags(0)
+				}
+				fn control_chars(&mut self) -> &mut [u8] {
+					&mut self.0[CONTROL_CHARS_OFFSET..]
+				}
+			}
+
+			// signals are left on, so Ctrl-C still works the same as it does in normal mode
+			pub fn set_stdin_raw(enabled: bool) -> Result<bool, std::io::Error> {
+				// SAFETY: isatty() has no preconditions
+				if unsafe {isatty(STDIN_FILENO)} == 0 {return Ok(false);}
+				if enabled {
+					modify_stdin(|termios| {
+						*termios.local_flags() &= !(ICANON | ECHO | IEXTEN);
+						*termios.input_flags() &= !IXON;
+						termios.control_chars()[VMIN] = 1;
+						termios.control_chars()[VTIME] = 0;
+					})?;
+				} else {
+					restore_stdin()?;
+				}
+				Ok(true)
+			}
+		}
+
+		// `None` means that the end of stdin was reached
+		pub fn read_stdin_byte(timeout: Option<Duration>) -> Result<Option<u8>, std::io::Error> {
+			if let Some(timeout) = timeout && !wait_for_stdin(timeout)? {
+				return Err(std::io::Error::from(std::io::ErrorKind::TimedOut));
+			}
+			let mut byte = 0u8;
+			loop {
+				// SAFETY: the buffer is one byte long
+				match unsafe {read(STDIN_FILENO, (&raw mut byte).cast(), 1)} {
+					1 => return Ok(Some(byte)),
+					0 => return Ok(None),
+					_ => {
+						let err = std::io::Error::last_os_error();
+						if err.kind() != std::io::ErrorKind::Interrupted {return Err(err);}
+					}
+				}
+			}
+		}
+
+		pub fn terminal_width() -> Option<usize> {
+			let mut win_size = WinSize::default();
+			// SAFETY: TIOCGWINSZ writes a `struct winsize`
+			if unsafe {ioctl(STDOUT_FILENO, TIOCGWINSZ, &mut win_size)} != 0 || win_size.cols == 0 {return None;}
+			Some(win_size.cols as usize)
+		}
+	}
+}
+
+
+
+#[cfg(windows)]
+mod windows {
+	use std::{ffi::c_void, sync::atomic::{AtomicBool, AtomicU32, Ordering}, time::Duration};
+
+	const STD_INPUT_HANDLE: u32 = -10i32 as u32;
+	const ENABLE_ECHO_INPUT: u32 = 0x0004;
+	const WAIT_OBJECT_0: u32 = 0;
+	const WAIT_TIMEOUT: u32 = 0x102;
+
+	type CtrlHandler = unsafe extern "system" fn(ctrl_type: u32) -> i32;
+
+	#[link(name = "kernel32")]
+	unsafe extern "system" {
+		fn GetStdHandle(std_handle: u32) -> *mut c_void;
+		fn GetConsoleMode(console_handle: *mut c_void, mode: *mut u32) -> i32;
+		fn SetConsoleMode(console_handle: *mut c_void, mode: u32) -> i32;
+		fn SetConsoleCtrlHandler(handler: Option<CtrlHandler>, add: i32) -> i32;
+		fn WaitForSingleObject(handle: *mut c_void, milliseconds: u32) -> u32;
+	}
+
+	static SAVED_MODE: AtomicU32 = AtomicU32::new(0);
+	static ECHO_DISABLED: AtomicBool = AtomicBool::new(false);
+
+	pub fn set_stdin_echo(enabled: bool) -> Result<bool, std::io::Error> {
+		// SAFETY: GetStdHandle() has no preconditions
+		let handle = unsafe {GetStdHandle(STD_INPUT_HANDLE)};
+		let mut mode = 0;
+		// SAFETY: GetConsoleMode() fails for handles that aren't consoles
+		if handle.is_null() || handle as isize == -1 || unsafe {GetConsoleMode(handle, &mut mode)} == 0 {return Ok(false);}
+		if enabled {
+			if !ECHO_DISABLED.swap(false, Ordering::SeqCst) {return Ok(true);}
+			// SAFETY: the handle is a console
+			let result = unsafe {SetConsoleMode(handle, SAVED_MODE.load(Ordering::SeqCst))};
+			// SAFETY: this removes the handler that was added when echo was disabled
+			unsafe {SetConsoleCtrlHandler(Some(restore_on_ctrl), 0);}
+			if result == 0 {return Err(std::io::Error::last_os_error());}
+			return Ok(true);
+		}
+		if ECHO_DISABLED.load(Ordering::SeqCst) {return Ok(true);}
+		SAVED_MODE.store(mode, Ordering::SeqCst);
+		ECHO_DISABLED.store(true, Ordering::SeqCst);
+		// SAFETY: the handler only restores the console mode
+		unsafe {SetConsoleCtrlHandler(Some(restore_on_ctrl), 1);}
+		// SAFETY: the handle is a console
+		if unsafe {SetConsoleMode(handle, mode & !ENABLE_ECHO_INPUT)} == 0 {
+			let err = std::io::Error::last_os_error();
+			ECHO_DISABLED.store(false, Ordering::SeqCst);
+			// SAFETY: this removes the handler that was just added
+			unsafe {SetConsoleCtrlHandler(Some(restore_on_ctrl), 0);}
+			return Err(err);
+		}
+		Ok(true)
+	}
+
+	// the console is signaled by any input event, not just a finished line, so this can return true before a line is typed
+	pub fn wait_for_stdin(timeout: Duration) -> Result<bool, std::io::Error> {
+		let milliseconds = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX - 1);
+		// SAFETY: GetStdHandle() has no preconditions, and WaitForSingleObject() fails for invalid handles
+		match unsafe {WaitForSingleObject(GetStdHandle(STD_INPUT_HANDLE), milliseconds)} {
+			WAIT_OBJECT_0 => Ok(true),
+			WAIT_TIMEOUT => Ok(false),
+			_ => Err(std::io::Error::last_os_error()),
+		}
+	}
+
+	unsafe extern "system" fn restore_on_ctrl(_ctrl_type: u32) -> i32 {
+		if ECHO_DISABLED.swap(false, Ordering::SeqCst) {
+			// SAFETY: the console mode was saved before echo was disabled
+			unsafe {SetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), SAVED_MODE.load(Ordering::SeqCst));}
+		}
+		0 // let the next handler run, which exits the process by default
+	}
+}
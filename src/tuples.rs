@@ -0,0 +1,150 @@
+use crate::*;
+
+
+
+/// Tuples of inputs, which are read from a single line
+/// 
+/// By default the values are separated by whitespace, but any separator can be used with `Separated`. Each value goes through its own type's parsing and validation, and the tuple of outputs is returned
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// # use smart_read::prelude::*;
+/// let (width, height) = prompt!("Enter the width and height: "; (U32Input, U32Input));
+/// let (x, y, scale) = prompt!("Enter x, y, and scale: "; Separated(",", (I32Input, I32Input, 0.0..=10.0)));
+/// ```
+/// 
+/// This is implemented for tuples of 2 to 6 types that implement `PromptHooks`
+pub trait TupleInput {
+	/// The tuple of outputs
+	type Output;
+	/// How many values are in the tuple
+	const LEN: usize;
+	/// Parses and validates each value, the error message says which value was invalid
+	fn parse_values(&self, values: &[&str]) -> Result<Self::Output, String>;
+	/// Formats each value of the default value
+	fn render_values(&self, default: &Self::Output) -> Vec<String>;
+}
+
+
+
+/// Reads a tuple of inputs from a single line, with the values separated by the given string instead of whitespace
+/// 
+/// Whitespace around each value is ignored, so `Separated(",", (I32Input, I32Input))` accepts both `1,2` and `1, 2`
+#[derive(Clone, Copy)]
+pub struct Separated<T>(pub &'static str, pub T);
+
+impl<T: TupleInput> PromptHooks for Separated<T> {
+	type Output = T::Output;
+	fn default_prompt(&self) -> String {
+		format!("Enter {} values separated by \"{}\": ", T::LEN, self.0)
+	}
+	fn render_default(&self, default: &Self::Output) -> String {
+		self.1.render_values(default).join(self.0)
+	}
+	fn parse(&self, input: String) -> Result<Self::Output, String> {
+		let values = input.split(self.0).map(str::trim).collect::<Vec<_>>();
+		if values.len() != T::LEN {
+			return Err(format!("Invalid input, expected {} values separated by \"{}\" but found {}", T::LEN, self.0, values.len()));
+		}
+		self.1.parse_values(&values)
+	}
+}
+
+impl<T: TupleInput> TryRead for Separated<T> {
+	type Output = T::Output;
+	type Default = T::Output;
+	fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+		run_prompt_loop(term, &self, prompt, default)
+	}
+}
+
+
+
+fn parse_value<T: PromptHooks>(input: &T, value: &str, value_num: usize) -> Result<T::Output, String> {
+	input.parse_and_validate(value.to_string()).map_err(|error_message| format!("Invalid value #{value_num} ({value:?}): {error_message}"))
+}
+
+macro_rules! implement_tuple_input {
+	($len:expr; $($type:ident $index:tt),*) => {
+		impl<$($type: PromptHooks),*> TupleInput for ($($type,)*) {
+			type Output = ($($type::Output,)*);
+			const LEN: usize = $len;
+			fn parse_values(&self, values: &[&str]) -> Result<Self::Output, String> {
+				Ok(($(parse_value(&self.$index, values[$index], $index + 1)?,)*))
+			}
+			fn render_values(&self, default: &Self::Output) -> Vec<String> {
+				vec!($(self.$index.render_default(&default.$index)),*)
+			}
+		}
+		impl<$($type: PromptHooks),*> PromptHooks for ($($type,)*) {
+			type Output = ($($type::Output,)*);
+			fn default_prompt(&self) -> String {
+				format!("Enter {} values separated by spaces: ", $len)
+			}
+			fn render_default(&self, default: &Self::Output) -> String {
+				self.render_values(default).join(" ")
+			}
+			fn parse(&self, input: String) -> Result<Self::Output, String> {
+				let values = input.split_whitespace().collect::<Vec<_>>();
+				if values.len() != $len {
+					return Err(format!("Invalid input, expected {} values separated by spaces but found {}", $len, values.len()));
+				}
+				self.parse_values(&values)
+			}
+		}
+		impl<$($type: PromptHooks),*> TryRead for ($($type,)*) {
+			type Output = ($($type::Output,)*);
+			type Default = ($($type::Output,)*);
+			fn try_read_with(self, term: &mut Terminal, prompt: Option<String>, default: Option<Self::Default>) -> ReadResult<Self::Output> {
+				run_prompt_loop(term, &self, prompt, default)
+			}
+		}
+	};
+}
+
+implement_tuple_input!(2; A 0, B 1);
+implement_tuple_input!(3; A 0, B 1, C 2);
+implement_tuple_input!(4; A 0, B 1, C 2, D 3);
+implement_tuple_input!(5; A 0, B 1, C 2, D 3, E 4);
+implement_tuple_input!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use basics::{F64Input, I32Input, UsizeInput};
+	use testing::ScriptedTerminal;
+	
+	#[test]
+	fn values_are_split_on_whitespace() {
+		let script = ScriptedTerminal::new(&["  3 \t-4  0.5 "]);
+		let output = (UsizeInput, I32Input, F64Input).try_read_with(&mut script.terminal(), None, None);
+		assert_eq!(output.ok(), Some((3, -4, 0.5)));
+		script.assert_shown("Enter 3 values separated by spaces: ");
+	}
+	
+	#[test]
+	fn invalid_values_are_named() {
+		let script = ScriptedTerminal::new(&["1 2 3", "1 x", "1 2"]);
+		let output = (UsizeInput, 1..=5).try_read_with(&mut script.terminal(), None, None);
+		assert_eq!(output.ok(), Some((1, 2)));
+		script.assert_shown("Invalid input, expected 2 values separated by spaces but found 3");
+		script.assert_shown("Invalid value #2 (\"x\"): Could not parse input");
+		script.assert_all_consumed();
+	}
+	
+	#[test]
+	fn separated_values_are_trimmed() {
+		let script = ScriptedTerminal::new(&["1,2", "", "4 , 5"]);
+		let mut term = script.terminal();
+		let output = Separated(",", (UsizeInput, UsizeInput)).try_read_with(&mut term, None, Some((7, 8)));
+		assert_eq!(output.ok(), Some((1, 2)));
+		script.assert_shown("Enter 2 values separated by \",\": (default: 7,8) ");
+		let output = Separated(",", (UsizeInput, UsizeInput)).try_read_with(&mut term, None, Some((7, 8)));
+		assert_eq!(output.ok(), Some((7, 8)));
+		let output = Separated(",", (UsizeInput, UsizeInput)).try_read_with(&mut term, None, None);
+		assert_eq!(output.ok(), Some((4, 5)));
+	}
+}